@@ -0,0 +1,17 @@
+use crate::{Uniboxed, UniBoxError};
+
+/// Uniboxes that can be cloned, if the hosted value was stored with [`CloneUniboxed::new_cloneable`].
+/// 
+/// The clone function of the hosted type is recorded at construction, like the drop function, so heterogeneous collections of uniboxes can be duplicated.
+pub trait CloneUniboxed: Uniboxed + Clone {
+    /// Create a new UniBox instance that can be cloned, cloning the hosted value.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    fn new_cloneable<T: Clone + 'static>(instance: T) -> Result<Self, UniBoxError>;
+    /// The unibox can be cloned: it's empty or was created with [`CloneUniboxed::new_cloneable`].
+    fn is_cloneable(&self) -> bool;
+    /// Clone the unibox, with its id, metadata and hooks.
+    /// 
+    /// Returns None if the unibox is not cloneable or the allocation failed. [`Clone::clone`] panics instead.
+    fn try_clone(&self) -> Option<Self>;
+}
@@ -0,0 +1,128 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    hint,
+    ops::Drop,
+    panic::Location,
+    sync::atomic::{
+        AtomicBool,
+        AtomicUsize,
+        Ordering
+    }
+};
+
+/// Max number of live uniboxes the registry can track. Uniboxes created once it's full are only counted.
+pub const LIVE_CAPACITY: usize = 64;
+
+#[derive(Clone, Copy)]
+struct Live {
+    id: &'static str,
+    len: usize,
+    location: &'static Location<'static>
+}
+
+struct Registry {
+    lock: AtomicBool,
+    slots: UnsafeCell<[Option<Live>; LIVE_CAPACITY]>,
+    untracked: AtomicUsize
+}
+
+unsafe impl Sync for Registry {}
+
+impl Registry {
+    fn with<R>(&self, f: impl FnOnce(&mut [Option<Live>; LIVE_CAPACITY]) -> R) -> R {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            hint::spin_loop();
+        }
+        let result = f(unsafe { &mut *self.slots.get() });
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+}
+
+static REGISTRY: Registry = Registry {
+    lock: AtomicBool::new(false),
+    slots: UnsafeCell::new([None; LIVE_CAPACITY]),
+    untracked: AtomicUsize::new(0)
+};
+
+/// Entry of a unibox in the live registry, removed when dropped.
+pub(crate) enum LiveToken {
+    /// Registered in a slot.
+    Tracked(usize),
+    /// Only counted, the registry was full.
+    Untracked,
+    /// Created in const context, not registered.
+    Const
+}
+
+impl LiveToken {
+    #[track_caller]
+    pub(crate) fn new(id: &'static str, len: usize) -> Self {
+        let live = Live {
+            id,
+            len,
+            location: Location::caller()
+        };
+        let slot = REGISTRY.with(|slots| {
+            let slot = slots.iter().position(Option::is_none)?;
+            slots[slot] = Some(live);
+            Some(slot)
+        });
+        match slot {
+            Some(slot) => Self::Tracked(slot),
+            None => {
+                REGISTRY.untracked.fetch_add(1, Ordering::Relaxed);
+                Self::Untracked
+            }
+        }
+    }
+
+    /// Token of a unibox created in const context, that can't be registered.
+    pub(crate) const fn new_const() -> Self {
+        Self::Const
+    }
+}
+
+impl LiveToken {
+    pub(crate) fn set(&mut self, id: &'static str, len: usize) {
+        if let Self::Tracked(slot) = *self {
+            REGISTRY.with(|slots| {
+                if let Some(live) = &mut slots[slot] {
+                    live.id = id;
+                    live.len = len;
+                }
+            });
+        }
+    }
+}
+
+impl Drop for LiveToken {
+    fn drop(&mut self) {
+        match *self {
+            Self::Tracked(slot) => REGISTRY.with(|slots| slots[slot] = None),
+            Self::Untracked => {
+                REGISTRY.untracked.fetch_sub(1, Ordering::Relaxed);
+            },
+            Self::Const => {}
+        }
+    }
+}
+
+/// Write the id, length and creation location of all currently live uniboxes, one per line.
+/// 
+/// Can be used from a panic handler or a debug shell, but it will spin forever if the panic happened while the registry was locked by a unibox being created or dropped. For the same reason, the writer must not create or drop uniboxes.
+pub fn dump_live(w: &mut impl fmt::Write) -> fmt::Result {
+    REGISTRY.with(|slots| {
+        for live in slots.iter().flatten() {
+            let id = if live.id.is_empty() { "<empty>" } else { live.id };
+            writeln!(w, "{} ({} bytes) created at {}", id, live.len, live.location)?;
+        }
+        Ok(())
+    })?;
+    let untracked = REGISTRY.untracked.load(Ordering::Relaxed);
+    if untracked > 0 {
+        writeln!(w, "... and {} untracked uniboxes", untracked)?;
+    }
+    Ok(())
+}
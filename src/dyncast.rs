@@ -0,0 +1,43 @@
+/// Coercion of a hosted type `T` to the trait object `Self::Dyn`, to host values with `new_dyn` and access them with [`Uniboxed::as_dyn`](crate::Uniboxed::as_dyn). Implemented with [`unibox_dyn!`](crate::unibox_dyn).
+///
+/// The coercion can't be expressed with generics in stable Rust, so each trait object gets its own type implementing this trait for all the types that implement the trait.
+///
+/// # Safety
+///
+/// `cast` must return the same pointer, only adding the metadata of `Self::Dyn`.
+pub unsafe trait DynCast<T> {
+    /// Trait object type, like `dyn Display`.
+    type Dyn: ?Sized + 'static;
+    /// Coerce a pointer to the hosted value into a trait object pointer.
+    fn cast(hosted: *mut T) -> *mut Self::Dyn;
+}
+
+/// Declare a type implementing [`DynCast`] for a trait object, to host any value implementing the traits with `new_dyn`, e.g. [`UniBoxN::new_dyn`](crate::UniBoxN::new_dyn).
+///
+/// The trait object is given as `dyn Trait`, optionally followed by `+ OtherTrait`. The hosted types must be `'static`.
+///
+/// ```
+/// use core::fmt::Display;
+/// use unibox::{ unibox_dyn, Uniboxed, UniBox32 };
+///
+/// unibox_dyn!(pub DisplayDyn = dyn Display);
+///
+/// let ubox = UniBox32::new_dyn::<DisplayDyn, _>(10u32).expect("Couldn't create UniBox32");
+/// let display = ubox.as_dyn::<dyn Display>().expect("Not a Display");
+/// assert_eq!(format!("{}", display), "10");
+/// ```
+#[macro_export]
+macro_rules! unibox_dyn {
+    ($(#[$attr:meta])* $vis:vis $name:ident = dyn $($bound:tt)+) => {
+        $(#[$attr])*
+        $vis struct $name;
+
+        unsafe impl<T: $($bound)+ + 'static> $crate::DynCast<T> for $name {
+            type Dyn = dyn $($bound)+;
+
+            fn cast(hosted: *mut T) -> *mut Self::Dyn {
+                hosted
+            }
+        }
+    };
+}
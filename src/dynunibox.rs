@@ -0,0 +1,249 @@
+use core::{
+    alloc::Layout,
+    any::TypeId
+};
+use crate::{AccessError, Buffer, TypeMeta, Uniboxed, UniBoxN, UniBoxRegion};
+
+/// Hook called right before the hosted value of a unibox is dropped. See [`Uniboxed::set_on_drop`].
+pub type OnDropHook = fn(&mut dyn DynUnibox);
+
+/// Object-safe companion of [`Uniboxed`], implemented by all uniboxes.
+/// 
+/// Allows mixing different kinds of uniboxes in the same collection, like `Vec<Box<dyn DynUnibox>>` holding a [`UniBox32`](crate::UniBox32) and a [`UniBox`](crate::UniBox).
+/// 
+/// Methods have the same names as in [`Uniboxed`]. If both traits are in scope, call them on concrete types with the trait path, like `Uniboxed::id(&ubox)`.
+pub trait DynUnibox {
+    /// Type identifier.
+    fn id(&self) -> &'static str;
+    /// Stored data length.
+    fn len(&self) -> usize;
+    /// Size and alignment of the hosted type. See [`Uniboxed::layout`].
+    fn layout(&self) -> Layout;
+    /// The unibox hosts no value.
+    fn is_empty(&self) -> bool;
+    /// Stored data as a byte slice. See [`Uniboxed::as_bytes`].
+    /// 
+    /// # Safety
+    /// 
+    /// Every byte of the hosted value must be initialized.
+    unsafe fn as_bytes(&self) -> &[u8];
+    /// Raw pointer to stored data.
+    fn as_ptr(&self) -> *const u8;
+    /// Raw mutable pointer to stored data.
+    fn as_mut_ptr(&mut self) -> *mut u8;
+    /// [`TypeId`] of the hosted type, only known if the unibox was created with [`Uniboxed::new_any`] or a constructor that compares values, like [`Uniboxed::new_eq`].
+    fn hosted_type_id(&self) -> Option<TypeId>;
+    /// Description of the hosted type. See [`Uniboxed::type_meta`].
+    fn type_meta(&self) -> Option<&'static TypeMeta>;
+    /// User metadata word attached to the hosted value.
+    fn meta(&self) -> usize;
+}
+
+impl<U: Uniboxed> DynUnibox for U {
+    fn id(&self) -> &'static str {
+        Uniboxed::id(self)
+    }
+
+    fn len(&self) -> usize {
+        Uniboxed::len(self)
+    }
+
+    fn layout(&self) -> Layout {
+        Uniboxed::layout(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Uniboxed::is_empty(self)
+    }
+
+    unsafe fn as_bytes(&self) -> &[u8] {
+        Uniboxed::as_bytes(self)
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        Uniboxed::as_ptr(self)
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        Uniboxed::as_mut_ptr(self)
+    }
+
+    fn hosted_type_id(&self) -> Option<TypeId> {
+        Uniboxed::hosted_type_id(self)
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        Uniboxed::type_meta(self)
+    }
+
+    fn meta(&self) -> usize {
+        Uniboxed::meta(self)
+    }
+}
+
+impl<B: Buffer> DynUnibox for UniBoxN<B> {
+    fn id(&self) -> &'static str {
+        UniBoxN::id(self)
+    }
+
+    fn len(&self) -> usize {
+        UniBoxN::len(self)
+    }
+
+    fn layout(&self) -> Layout {
+        UniBoxN::layout(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        UniBoxN::is_empty(self)
+    }
+
+    unsafe fn as_bytes(&self) -> &[u8] {
+        UniBoxN::as_bytes(self)
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        UniBoxN::as_ptr(self)
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        UniBoxN::as_mut_ptr(self)
+    }
+
+    fn hosted_type_id(&self) -> Option<TypeId> {
+        UniBoxN::hosted_type_id(self)
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        UniBoxN::type_meta(self)
+    }
+
+    fn meta(&self) -> usize {
+        UniBoxN::meta(self)
+    }
+}
+
+impl DynUnibox for UniBoxRegion {
+    fn id(&self) -> &'static str {
+        UniBoxRegion::id(self)
+    }
+
+    fn len(&self) -> usize {
+        UniBoxRegion::len(self)
+    }
+
+    fn layout(&self) -> Layout {
+        UniBoxRegion::layout(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        UniBoxRegion::is_empty(self)
+    }
+
+    unsafe fn as_bytes(&self) -> &[u8] {
+        UniBoxRegion::as_bytes(self)
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        UniBoxRegion::as_ptr(self)
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        UniBoxRegion::as_mut_ptr(self)
+    }
+
+    fn hosted_type_id(&self) -> Option<TypeId> {
+        UniBoxRegion::hosted_type_id(self)
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        UniBoxRegion::type_meta(self)
+    }
+
+    fn meta(&self) -> usize {
+        UniBoxRegion::meta(self)
+    }
+}
+
+impl dyn DynUnibox {
+    /// Check if the provided and hosted types are the same.
+    pub fn check_type<T>(&self) -> bool {
+        !self.is_empty() && self.len() == core::mem::size_of::<T>() && self.id() == core::any::type_name::<T>()
+    }
+
+    /// Get reference to stored data using a type.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// Note that on a `Box<dyn DynUnibox>`, `as_ref` resolves to [`AsRef`] of the Box. Dereference it first: `(*ubox).as_ref::<T>()`.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn as_ref<T: Sized>(&self) -> &T {
+        self.integrity_checks::<T>();
+        &*(self.as_ptr() as *const T)
+    }
+
+    /// Get mutable reference to stored data using a type.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
+        self.integrity_checks::<T>();
+        &mut *(self.as_mut_ptr() as *mut T)
+    }
+
+    /// Get reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_as_ref<T: Sized>(&self) -> Result<&T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&*(self.as_ptr() as *const T))
+    }
+
+    /// Get mutable reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_as_mut_ref<T: Sized>(&mut self) -> Result<&mut T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&mut *(self.as_mut_ptr() as *mut T))
+    }
+
+    /// Get reference to stored data if it is of type `T`. See [`Uniboxed::downcast_ref`].
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &*(self.as_ptr() as *const T) })
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get mutable reference to stored data if it is of type `T`. See [`Uniboxed::downcast_mut`].
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &mut *(self.as_mut_ptr() as *mut T) })
+        }
+        else {
+            None
+        }
+    }
+
+    fn integrity_checks<T>(&self) {
+        if !self.check_type::<T>() {
+            panic!("Hosted and requiered types are different");
+        }
+    }
+}
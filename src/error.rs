@@ -0,0 +1,153 @@
+use core::{
+    alloc::Layout,
+    fmt
+};
+
+/// Error accessing the data hosted by a unibox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessError {
+    /// The unibox hosts no value.
+    Empty,
+    /// The requested type is not the hosted one.
+    TypeMismatch {
+        /// Id of the hosted type.
+        hosted: &'static str,
+        /// Name of the requested type.
+        requested: &'static str
+    }
+}
+
+impl AccessError {
+    /// Check that type `T` can be accessed in a unibox with the given state.
+    pub(crate) fn check<T>(empty: bool, id: &'static str, len: usize) -> Result<(), Self> {
+        let requested = core::any::type_name::<T>();
+        if empty {
+            Err(Self::Empty)
+        }
+        else if len != core::mem::size_of::<T>() || id != requested {
+            Err(Self::TypeMismatch { hosted: id, requested })
+        }
+        else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "unibox is empty"),
+            Self::TypeMismatch { hosted, requested } => write!(f, "unibox hosts {}, not {}", hosted, requested)
+        }
+    }
+}
+
+impl core::error::Error for AccessError {}
+
+/// Error creating a unibox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniBoxError {
+    /// The type doesn't fit in a static unibox.
+    TooBig {
+        /// Size of the type.
+        size: usize,
+        /// Capacity of the unibox.
+        capacity: usize
+    },
+    /// Couldn't allocate memory for the type.
+    AllocFailed(Layout),
+    /// A value can't be rebuilt from zero bytes.
+    ZeroSized,
+    /// The requested alignment is not a power of two.
+    InvalidAlign(usize),
+    /// The type needs a bigger alignment than the buffer of a static unibox.
+    Misaligned {
+        /// Alignment of the type.
+        align: usize,
+        /// Alignment of the buffer.
+        buffer_align: usize
+    }
+}
+
+impl UniBoxError {
+    /// Capacity of the smallest static unibox alias, from [`UniBox8`](crate::UniBox8) to [`UniBox4096`](crate::UniBox4096), that fits the type rejected with [`UniBoxError::TooBig`].
+    /// 
+    /// Returns None for other errors, or if the type is bigger than 4096 bytes. The alignment of the type is not checked.
+    pub fn suggested_capacity(&self) -> Option<usize> {
+        match self {
+            Self::TooBig { size, .. } => crate::stack::SIZE_CLASSES.iter().copied().find(|class| class >= size),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for UniBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooBig { size, capacity } => write!(f, "type of {} bytes doesn't fit in a unibox of {} bytes", size, capacity),
+            Self::AllocFailed(layout) => write!(f, "couldn't allocate {} bytes aligned to {}", layout.size(), layout.align()),
+            Self::ZeroSized => write!(f, "can't rebuild a value from zero bytes"),
+            Self::InvalidAlign(align) => write!(f, "alignment {} is not a power of two", align),
+            Self::Misaligned { align, buffer_align } => write!(f, "type aligned to {} bytes doesn't fit in a unibox aligned to {}", align, buffer_align)
+        }
+    }
+}
+
+impl core::error::Error for UniBoxError {}
+
+/// Error exchanging a unibox through a shared-memory segment, see [`Uniboxed::write_shared`](crate::Uniboxed::write_shared).
+#[cfg(feature = "bytemuck")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedError {
+    /// The hosted value is not Pod, or the unibox is empty.
+    NotPod,
+    /// The segment can't hold the header and the payload.
+    TooSmall {
+        /// Bytes needed.
+        size: usize,
+        /// Length of the segment.
+        available: usize
+    },
+    /// The segment doesn't start with a [`SharedHeader`](crate::SharedHeader).
+    BadMagic,
+    /// The header was written with another version of the format.
+    Version(u32),
+    /// The layout of the payload is not the one of the requested type.
+    LayoutMismatch {
+        /// Size of the payload.
+        size: usize,
+        /// Alignment of the payload.
+        align: usize,
+        /// Layout of the requested type.
+        expected: Layout
+    },
+    /// The payload has the same layout, but is not of the requested type.
+    TypeMismatch(&'static str),
+    /// Couldn't create the unibox.
+    Unibox(UniBoxError)
+}
+
+#[cfg(feature = "bytemuck")]
+impl From<UniBoxError> for SharedError {
+    fn from(error: UniBoxError) -> Self {
+        Self::Unibox(error)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotPod => write!(f, "unibox doesn't host a Pod value"),
+            Self::TooSmall { size, available } => write!(f, "record of {} bytes doesn't fit in a segment of {} bytes", size, available),
+            Self::BadMagic => write!(f, "segment doesn't hold a unibox record"),
+            Self::Version(version) => write!(f, "unsupported record version {}", version),
+            Self::LayoutMismatch { size, align, expected } => write!(f, "payload of {} bytes aligned to {} is not {} bytes aligned to {}", size, align, expected.size(), expected.align()),
+            Self::TypeMismatch(requested) => write!(f, "payload is not {}", requested),
+            Self::Unibox(error) => error.fmt(f)
+        }
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl core::error::Error for SharedError {}
@@ -0,0 +1,65 @@
+use core::{
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    marker::PhantomData
+};
+
+/// Typed reference to a value hosted in an arena, like [`UniArena`](crate::UniArena) or [`UniBump`](crate::UniBump).
+/// 
+/// It's just an index, so it's `Copy` and doesn't borrow the arena. When accessing the value, the arena checks that the handle is not from before a reset and that `T` is the hosted type, so a stale handle gives None instead of another value. Handles of a different arena are not detected, but they can only give a value of type `T`.
+pub struct ArenaHandle<T> {
+    pub(crate) index: usize,
+    pub(crate) epoch: u32,
+    _type: PhantomData<fn() -> T>
+}
+
+impl<T> ArenaHandle<T> {
+    pub(crate) fn new(index: usize, epoch: u32) -> Self {
+        Self {
+            index,
+            epoch,
+            _type: PhantomData
+        }
+    }
+
+    /// Position of the value in the arena, in allocation order.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+// Derives would require `T` to implement the traits too
+impl<T> Clone for ArenaHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaHandle<T> {}
+
+impl<T> PartialEq for ArenaHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.epoch == other.epoch
+    }
+}
+
+impl<T> Eq for ArenaHandle<T> {}
+
+impl<T> Hash for ArenaHandle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.epoch.hash(state);
+    }
+}
+
+impl<T> Debug for ArenaHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaHandle").field("index", &self.index).field("epoch", &self.epoch).finish()
+    }
+}
@@ -0,0 +1,63 @@
+use core::{
+    alloc::Layout,
+    mem,
+    ptr,
+    sync::atomic::{
+        AtomicPtr,
+        AtomicUsize,
+        Ordering
+    }
+};
+extern crate alloc;
+
+/// Handler invoked by [`UniBox`](crate::UniBox) when an allocation fails.
+pub type AllocErrorHook = fn(Layout);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+static RETRIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Register a global handler called every time a [`UniBox`](crate::UniBox) allocation fails, before retrying or returning Err.
+/// 
+/// Use it to log the failure, or to free some memory (e.g. flush a cache) so the next retry can succeed. Pass `None` to remove it.
+pub fn set_alloc_error_hook(hook: Option<AllocErrorHook>) {
+    let hook = match hook {
+        Some(hook) => hook as *mut (),
+        None => ptr::null_mut()
+    };
+    HOOK.store(hook, Ordering::Release);
+}
+
+/// Set how many times a failed [`UniBox`](crate::UniBox) allocation is retried. Default is 0.
+pub fn set_alloc_retries(retries: usize) {
+    RETRIES.store(retries, Ordering::Release);
+}
+
+/// Allocate memory, calling the error hook and retrying on failure.
+pub(crate) fn alloc(layout: Layout) -> *mut u8 {
+    alloc_with(layout, alloc::alloc::alloc)
+}
+
+/// Allocate zeroed memory, calling the error hook and retrying on failure.
+#[cfg(feature = "bytemuck")]
+pub(crate) fn alloc_zeroed(layout: Layout) -> *mut u8 {
+    alloc_with(layout, alloc::alloc::alloc_zeroed)
+}
+
+fn alloc_with(layout: Layout, allocator: unsafe fn(Layout) -> *mut u8) -> *mut u8 {
+    let mut retries = RETRIES.load(Ordering::Acquire);
+    loop {
+        let buffer = unsafe { allocator(layout) };
+        if !buffer.is_null() {
+            return buffer;
+        }
+        let hook = HOOK.load(Ordering::Acquire);
+        if !hook.is_null() {
+            let hook = unsafe { mem::transmute::<*mut (), AllocErrorHook>(hook) };
+            hook(layout);
+        }
+        if retries == 0 {
+            return buffer;
+        }
+        retries -= 1;
+    }
+}
@@ -0,0 +1,168 @@
+use core::{
+    fmt::{
+        self,
+        Debug
+    },
+    ops::{
+        Deref,
+        Drop
+    },
+    ptr::NonNull
+};
+#[cfg(not(feature = "portable-atomic"))]
+use core::sync::atomic::{
+    fence,
+    AtomicUsize,
+    Ordering
+};
+#[cfg(feature = "portable-atomic")]
+use portable_atomic::{
+    fence,
+    AtomicUsize,
+    Ordering
+};
+use super::UniBox;
+use crate::{SyncUniBox, Uniboxed, UniBoxError};
+extern crate alloc;
+use alloc::boxed::Box;
+
+struct ArcInner {
+    count: AtomicUsize,
+    unibox: UniBox
+}
+
+/// Atomically reference-counted unibox, to share a hosted value among many owners in different threads or interrupt contexts. The thread-safe sibling of [`UniRc`](crate::UniRc).
+///
+/// It can only be created with values that are `Send` and `Sync`, so unlike other uniboxes it's both `Send` and `Sync`. It dereferences to a [`UniBox`], and the associated functions are called as `UniArc::strong_count(&arc)`.
+///
+/// On targets without atomic read-modify-write instructions, like `thumbv6m`, enable the `portable-atomic` feature. See the [`portable-atomic`](https://docs.rs/portable-atomic) crate for the options it needs on those targets.
+///
+/// ```
+/// use unibox::{UniArc, Uniboxed};
+///
+/// let config = UniArc::new_any([10u32, 20]).expect("Failed creating UniArc");
+/// let shared = config.clone();
+/// std::thread::spawn(move || {
+///     assert_eq!(shared.downcast_ref::<[u32; 2]>(), Some(&[10, 20]));
+/// }).join().unwrap();
+/// assert_eq!(UniArc::strong_count(&config), 1);
+/// ```
+pub struct UniArc {
+    inner: NonNull<ArcInner>
+}
+
+// Only created with Send and Sync values
+unsafe impl Send for UniArc {}
+unsafe impl Sync for UniArc {}
+
+impl UniArc {
+    /// Create a new UniArc instance. See [`UniBox::new`](crate::Uniboxed::new).
+    ///
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new<T: Send + Sync + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self::from_unibox(UniBox::new(instance)?))
+    }
+
+    /// Create a new UniArc instance that can be accessed safely with [`Uniboxed::downcast_ref`]. See [`UniBox::new_any`](crate::Uniboxed::new_any).
+    ///
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_any<T: Send + Sync + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self::from_unibox(UniBox::new_any(instance)?))
+    }
+
+    /// Number of owners of the hosted value. Other threads may change it right after it's read.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().count.load(Ordering::Acquire)
+    }
+
+    /// Both point to the same hosted value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.inner == other.inner
+    }
+
+    /// Get mutable access to the unibox if there are no other owners.
+    /// 
+    /// # Safety
+    /// 
+    /// The unibox must keep hosting a `Send` and `Sync` value, e.g. it can't be swapped with another unibox.
+    pub unsafe fn get_mut(this: &mut Self) -> Option<&mut UniBox> {
+        if this.inner().count.load(Ordering::Acquire) == 1 {
+            // No other owner can clone it, since this one is borrowed mutably
+            Some(&mut (*this.inner.as_ptr()).unibox)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get the unibox back if there are no other owners. Otherwise returns Err with the UniArc.
+    pub fn try_unwrap(this: Self) -> Result<UniBox, Self> {
+        if this.inner().count.compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            return Err(this);
+        }
+        let inner = unsafe { Box::from_raw(this.inner.as_ptr()) };
+        core::mem::forget(this);
+        Ok(inner.unibox)
+    }
+
+    /// Share *unibox*, that must host a `Send` and `Sync` value.
+    pub(crate) fn from_unibox(unibox: UniBox) -> Self {
+        let inner = Box::new(ArcInner {
+            count: AtomicUsize::new(1),
+            unibox
+        });
+        Self {
+            inner: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) }
+        }
+    }
+
+    fn inner(&self) -> &ArcInner {
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl From<SyncUniBox<UniBox>> for UniArc {
+    /// Share a unibox that is already known to host a `Send` and `Sync` value. The hosted value is not moved.
+    fn from(unibox: SyncUniBox<UniBox>) -> Self {
+        Self::from_unibox(unibox.into_unibox())
+    }
+}
+
+impl Clone for UniArc {
+    fn clone(&self) -> Self {
+        // Like Arc, a count this high can only come from leaked clones, and would overflow soon
+        if self.inner().count.fetch_add(1, Ordering::Relaxed) > isize::MAX as usize {
+            panic!("Too many UniArc clones");
+        }
+        Self {
+            inner: self.inner
+        }
+    }
+}
+
+impl Deref for UniArc {
+    type Target = UniBox;
+
+    fn deref(&self) -> &UniBox {
+        &self.inner().unibox
+    }
+}
+
+impl Drop for UniArc {
+    fn drop(&mut self) {
+        if self.inner().count.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        // Make the uses of the other owners happen before dropping the value
+        fence(Ordering::Acquire);
+        drop(unsafe { Box::from_raw(self.inner.as_ptr()) });
+    }
+}
+
+impl Debug for UniArc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UniArc").field(&**self).finish()
+    }
+}
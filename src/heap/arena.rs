@@ -0,0 +1,183 @@
+use core::{
+    alloc::Layout,
+    any::TypeId,
+    mem,
+    ops::Drop,
+    ptr::{
+        self,
+        NonNull
+    }
+};
+use crate::{ArenaHandle, TypeMeta, TypeMetaOf};
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// Minimum size of the buffer of a [`UniArena`], once it allocates.
+const MIN_CAPACITY: usize = 64;
+
+/// Value hosted in an arena.
+struct Entry {
+    offset: usize,
+    type_meta: &'static TypeMeta
+}
+
+/// Arena that hosts many values of different types in a single growing buffer, referenced by [`ArenaHandle`].
+/// 
+/// Values are placed one after the other, saving an allocation per value, e.g. for the nodes of an AST or a scene graph. They can't be removed one by one: dropping the arena, or calling [`UniArena::clear`], drops all of them.
+/// 
+/// When the buffer is full it's moved to a bigger allocation, so references to the values don't outlive a call to [`UniArena::alloc`], but handles stay valid.
+/// 
+/// ```
+/// use unibox::UniArena;
+/// 
+/// let mut arena = UniArena::new();
+/// let num = arena.alloc(10u64).expect("Couldn't allocate");
+/// let name = arena.alloc("node").expect("Couldn't allocate");
+/// *arena.get_mut(num).expect("Wrong handle") += 1;
+/// assert_eq!(arena.get(num), Some(&11));
+/// assert_eq!(arena.get(name), Some(&"node"));
+/// ```
+pub struct UniArena {
+    buffer: NonNull<u8>,
+    layout: Layout,
+    top: usize,
+    entries: Vec<Entry>,
+    epoch: u32
+}
+
+impl UniArena {
+    /// Create an empty arena, that doesn't allocate until a value is hosted.
+    pub const fn new() -> Self {
+        Self {
+            buffer: NonNull::dangling(),
+            layout: Layout::new::<()>(),
+            top: 0,
+            entries: Vec::new(),
+            epoch: 0
+        }
+    }
+
+    /// Create an empty arena with a buffer of at least *capacity* bytes, aligned to 16.
+    /// 
+    /// Returns None if the allocation fails.
+    pub fn with_capacity(capacity: usize) -> Option<Self> {
+        let mut arena = Self::new();
+        arena.grow(capacity, 16).ok()?;
+        Some(arena)
+    }
+
+    /// Host a value in the arena, and return its handle.
+    /// 
+    /// Returns Err with the value if the buffer has to grow and the allocation fails.
+    pub fn alloc<T: 'static>(&mut self, instance: T) -> Result<ArenaHandle<T>, T> {
+        let offset = match self.top.checked_add(mem::align_of::<T>() - 1) {
+            Some(top) => top & !(mem::align_of::<T>() - 1),
+            None => return Err(instance)
+        };
+        let end = match offset.checked_add(mem::size_of::<T>()) {
+            Some(end) => end,
+            None => return Err(instance)
+        };
+        if (end > self.layout.size() || mem::align_of::<T>() > self.layout.align()) && self.grow(end, mem::align_of::<T>()).is_err() {
+            return Err(instance);
+        }
+        unsafe {
+            ptr::write(self.buffer.as_ptr().add(offset) as *mut T, instance);
+        }
+        self.entries.push(Entry {
+            offset,
+            type_meta: &TypeMetaOf::<T>::ANY
+        });
+        self.top = end;
+        Ok(ArenaHandle::new(self.entries.len() - 1, self.epoch))
+    }
+
+    /// Get reference to the value of *handle*. None if the handle is from before a reset.
+    pub fn get<T: 'static>(&self, handle: ArenaHandle<T>) -> Option<&T> {
+        let offset = self.offset_of(handle)?;
+        Some(unsafe { &*(self.buffer.as_ptr().add(offset) as *const T) })
+    }
+
+    /// Get mutable reference to the value of *handle*. None if the handle is from before a reset.
+    pub fn get_mut<T: 'static>(&mut self, handle: ArenaHandle<T>) -> Option<&mut T> {
+        let offset = self.offset_of(handle)?;
+        Some(unsafe { &mut *(self.buffer.as_ptr().add(offset) as *mut T) })
+    }
+
+    /// Number of hosted values.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The arena hosts no values.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Bytes used by the hosted values, including alignment padding.
+    pub fn used(&self) -> usize {
+        self.top
+    }
+
+    /// Size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Drop all the hosted values, in reverse order, keeping the buffer. Handles created before are no longer valid.
+    pub fn clear(&mut self) {
+        while let Some(entry) = self.entries.pop() {
+            if let Some(drop_fn) = entry.type_meta.drop_fn {
+                drop_fn(unsafe { self.buffer.as_ptr().add(entry.offset) });
+            }
+        }
+        self.top = 0;
+        self.epoch = self.epoch.wrapping_add(1);
+    }
+
+    /// Offset of the value of *handle*, if it's still valid and hosts a `T`.
+    fn offset_of<T: 'static>(&self, handle: ArenaHandle<T>) -> Option<usize> {
+        if handle.epoch != self.epoch {
+            return None;
+        }
+        let entry = self.entries.get(handle.index)?;
+        if entry.type_meta.type_id() == Some(TypeId::of::<T>()) {
+            Some(entry.offset)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Move the values to a new allocation of at least *size* bytes, aligned to at least *align*.
+    fn grow(&mut self, size: usize, align: usize) -> Result<(), ()> {
+        let size = size.max(self.layout.size().saturating_mul(2)).max(MIN_CAPACITY);
+        let layout = Layout::from_size_align(size, align.max(self.layout.align())).map_err(|_| ())?;
+        let buffer = NonNull::new(super::allochook::alloc(layout)).ok_or(())?;
+        unsafe {
+            // Values are moved bitwise, like any Rust value
+            ptr::copy_nonoverlapping(self.buffer.as_ptr(), buffer.as_ptr(), self.top);
+            if self.layout.size() != 0 {
+                alloc::alloc::dealloc(self.buffer.as_ptr(), self.layout);
+            }
+        }
+        self.buffer = buffer;
+        self.layout = layout;
+        Ok(())
+    }
+}
+
+impl Default for UniArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for UniArena {
+    fn drop(&mut self) {
+        self.clear();
+        if self.layout.size() != 0 {
+            unsafe { alloc::alloc::dealloc(self.buffer.as_ptr(), self.layout) };
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use core::{
+    alloc::Layout,
+    cmp::Ordering,
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    mem
+};
+use super::UniBox;
+use crate::{AccessError, CloneUniboxed, DynCast, OnDropHook, TypeMeta, Uniboxed, UniBoxError, UniBoxSized};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "bytemuck")]
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "bytemuck")]
+use crate::SharedError;
+
+/// Unibox that stores values of up to N bytes inline, like a [`UniBoxSized`], and bigger values on the heap, like a [`UniBox`].
+///
+/// Values aligned to more than 16 bytes also go to the heap. The storage only depends on the hosted type, so all values of a type are stored the same way. Use [`AutoUniBox::is_inline`] to know which one is used.
+///
+/// ```
+/// use unibox::{ AutoUniBox, Uniboxed };
+///
+/// let small = AutoUniBox::<32>::new([0u8; 32]).expect("Couldn't create AutoUniBox");
+/// let big = AutoUniBox::<32>::new([0u8; 100]).expect("Couldn't create AutoUniBox");
+/// assert!(small.is_inline());
+/// assert!(!big.is_inline());
+/// assert_eq!(big.len(), 100);
+/// ```
+pub struct AutoUniBox<const N: usize> {
+    storage: Storage<N>,
+    on_drop: Option<OnDropHook>
+}
+
+enum Storage<const N: usize> {
+    Inline(UniBoxSized<N>),
+    Heap(UniBox)
+}
+
+/// Run the same code on the inline or heap unibox.
+macro_rules! dispatch {
+    ($storage:expr, $ubox:ident => $body:expr) => {
+        match $storage {
+            Storage::Inline($ubox) => $body,
+            Storage::Heap($ubox) => $body
+        }
+    };
+}
+
+/// Create an AutoUniBox with the constructor *new* of the inline or heap unibox, depending on the type.
+macro_rules! new_auto {
+    ($t:ty, $new:ident($instance:expr)) => {
+        Ok(
+            Self {
+                storage: if Self::fits::<$t>() {
+                    Storage::Inline(UniBoxSized::<N>::$new($instance)?)
+                }
+                else {
+                    Storage::Heap(UniBox::$new($instance)?)
+                },
+                on_drop: None
+            }
+        )
+    };
+}
+
+impl<const N: usize> AutoUniBox<N> {
+    /// Maximum size of a type stored inline, N bytes.
+    pub const fn capacity() -> usize {
+        N
+    }
+
+    /// The hosted value is stored inline. Empty uniboxes are always inline.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.storage, Storage::Inline(_))
+    }
+
+    /// Create a new unibox that wipes the hosted value on drop. See [`UniBox::new_zeroizing`].
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroizing<T: Zeroize + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        new_auto!(T, new_zeroizing(instance))
+    }
+
+    /// Create a new unibox hosting secret material, wiped after the hosted value is dropped. See [`UniBox::new_secret`].
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_secret<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        new_auto!(T, new_secret(instance))
+    }
+
+    /// Create a new unibox hosting a value that can be accessed as a trait object. See [`UniBoxN::new_dyn`](crate::UniBoxN::new_dyn).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_dyn<C: DynCast<T>, T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        if Self::fits::<T>() {
+            Ok(Self {
+                storage: Storage::Inline(UniBoxSized::<N>::new_dyn::<C, T>(instance)?),
+                on_drop: None
+            })
+        }
+        else {
+            Ok(Self {
+                storage: Storage::Heap(UniBox::new_dyn::<C, T>(instance)?),
+                on_drop: None
+            })
+        }
+    }
+
+    /// Create a new unibox hosting a zeroed `T`. See [`UniBox::new_zeroed`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroed<T: Zeroable + 'static>() -> Result<Self, UniBoxError> {
+        Ok(
+            Self {
+                storage: if Self::fits::<T>() {
+                    Storage::Inline(UniBoxSized::<N>::new_zeroed::<T>()?)
+                }
+                else {
+                    Storage::Heap(UniBox::new_zeroed::<T>()?)
+                },
+                on_drop: None
+            }
+        )
+    }
+
+    /// Create a new unibox hosting a Pod value, cloned by copying its bytes. See [`UniBox::new_pod`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_pod<T: Pod>(instance: T) -> Result<Self, UniBoxError> {
+        new_auto!(T, new_pod(instance))
+    }
+
+    /// Rebuild a unibox hosting a Pod value written to a shared-memory segment. See [`UniBoxN::from_shared`](crate::UniBoxN::from_shared).
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_shared<T: Pod>(segment: &[u8]) -> Result<Self, SharedError> {
+        let (instance, meta) = crate::shared::read_shared::<T>(segment)?;
+        Ok(Self::new_pod(instance)?.with_meta(meta))
+    }
+
+    /// A `T` is stored inline.
+    fn fits<T: Sized>() -> bool {
+        mem::size_of::<T>() <= N && mem::align_of::<T>() <= UniBoxSized::<N>::max_align()
+    }
+
+    /// Replace the storage, dropping the previous one, and keep the drop hook.
+    fn set_storage(&mut self, mut storage: Storage<N>) {
+        if let Some(hook) = self.on_drop {
+            dispatch!(&mut storage, ubox => ubox.set_on_drop(hook));
+        }
+        self.storage = storage;
+    }
+}
+
+impl<const N: usize> Uniboxed for AutoUniBox<N> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    unsafe fn new_unbounded<T: Sized>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_unbounded(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_any(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_eq<T: PartialEq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_eq(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_hashable<T: Hash + Eq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_hashable(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_ord<T: Ord + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_ord(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_debuggable<T: Debug + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        new_auto!(T, new_debuggable(instance))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn empty() -> Self {
+        Self {
+            storage: Storage::Inline(UniBoxSized::empty()),
+            on_drop: None
+        }
+    }
+
+    unsafe fn as_ref<T: Sized>(&self) -> &T {
+        dispatch!(&self.storage, ubox => ubox.as_ref())
+    }
+
+    unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
+        dispatch!(&mut self.storage, ubox => ubox.as_mut_ref())
+    }
+
+    unsafe fn take<T: Sized>(&mut self) -> T {
+        let instance = dispatch!(&mut self.storage, ubox => ubox.take());
+        if !self.is_inline() {
+            // Release the allocation, empty uniboxes are inline
+            self.set_storage(Storage::Inline(UniBoxSized::empty()));
+        }
+        instance
+    }
+
+    unsafe fn try_take<T: Sized>(&mut self) -> Result<T, AccessError> {
+        let instance = dispatch!(&mut self.storage, ubox => ubox.try_take())?;
+        if !self.is_inline() {
+            self.set_storage(Storage::Inline(UniBoxSized::empty()));
+        }
+        Ok(instance)
+    }
+
+    fn forget_inner(self) {
+        dispatch!(self.storage, ubox => ubox.forget_inner())
+    }
+
+    unsafe fn replace<T: Sized>(&mut self, instance: T) -> Result<Option<T>, T> {
+        match (&mut self.storage, Self::fits::<T>()) {
+            (Storage::Inline(ubox), true) => ubox.replace(instance),
+            (Storage::Heap(ubox), false) => ubox.replace(instance),
+            (_, true) => {
+                let mut ubox = UniBoxSized::empty();
+                ubox.replace(instance)?;
+                self.set_storage(Storage::Inline(ubox));
+                Ok(None)
+            },
+            (_, false) => {
+                let mut ubox = UniBox::empty();
+                ubox.replace(instance)?;
+                self.set_storage(Storage::Heap(ubox));
+                Ok(None)
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        dispatch!(&self.storage, ubox => ubox.is_empty())
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        dispatch!(&self.storage, ubox => ubox.as_ptr())
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        dispatch!(&mut self.storage, ubox => ubox.as_mut_ptr())
+    }
+
+    fn len(&self) -> usize {
+        dispatch!(&self.storage, ubox => ubox.len())
+    }
+
+    fn layout(&self) -> Layout {
+        dispatch!(&self.storage, ubox => ubox.layout())
+    }
+
+    fn id(&self) -> &'static str {
+        dispatch!(&self.storage, ubox => ubox.id())
+    }
+
+    fn set_id(&mut self, id: &'static str) {
+        dispatch!(&mut self.storage, ubox => ubox.set_id(id))
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        dispatch!(&self.storage, ubox => ubox.type_meta())
+    }
+
+    fn meta(&self) -> usize {
+        dispatch!(&self.storage, ubox => ubox.meta())
+    }
+
+    fn set_meta(&mut self, meta: usize) {
+        dispatch!(&mut self.storage, ubox => ubox.set_meta(meta))
+    }
+
+    fn set_on_drop(&mut self, hook: OnDropHook) {
+        self.on_drop = Some(hook);
+        dispatch!(&mut self.storage, ubox => ubox.set_on_drop(hook))
+    }
+
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        dispatch!(&self.storage, ubox => ubox.as_debug())
+    }
+
+    fn eq_dyn(&self, other: &Self) -> bool {
+        match (&self.storage, &other.storage) {
+            (Storage::Inline(a), Storage::Inline(b)) => a.eq_dyn(b),
+            (Storage::Heap(a), Storage::Heap(b)) => a.eq_dyn(b),
+            // Different types, the heap one is never empty
+            _ => false
+        }
+    }
+
+    fn cmp_dyn(&self, other: &Self) -> Ordering {
+        match (&self.storage, &other.storage) {
+            (Storage::Inline(a), Storage::Inline(b)) => a.cmp_dyn(b),
+            (Storage::Heap(a), Storage::Heap(b)) => a.cmp_dyn(b),
+            _ => {
+                other.is_empty().cmp(&self.is_empty()).then(self.id().cmp(other.id())).then(self.len().cmp(&other.len())).then(self.hosted_type_id().cmp(&other.hosted_type_id()))
+            }
+        }
+    }
+}
+
+impl<const N: usize> CloneUniboxed for AutoUniBox<N> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_cloneable<T: Clone + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        new_auto!(T, new_cloneable(instance))
+    }
+
+    fn is_cloneable(&self) -> bool {
+        dispatch!(&self.storage, ubox => ubox.is_cloneable())
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn try_clone(&self) -> Option<Self> {
+        Some(
+            Self {
+                storage: match &self.storage {
+                    Storage::Inline(ubox) => Storage::Inline(ubox.try_clone()?),
+                    Storage::Heap(ubox) => Storage::Heap(ubox.try_clone()?)
+                },
+                on_drop: self.on_drop
+            }
+        )
+    }
+}
+
+/// Prints the hosted value if the unibox was created with [`Uniboxed::new_debuggable`], otherwise its id and length.
+impl<const N: usize> Debug for AutoUniBox<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_debug() {
+            Some(debug) => debug.fmt(f),
+            None => f.debug_struct("AutoUniBox").field("id", &self.id()).field("len", &self.len()).field("inline", &self.is_inline()).finish_non_exhaustive()
+        }
+    }
+}
+
+/// Uses [`Uniboxed::eq_dyn`]. Uniboxes not created with [`Uniboxed::new_eq`] or [`Uniboxed::new_hashable`] are only equal to themselves.
+impl<const N: usize> PartialEq for AutoUniBox<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_dyn(other)
+    }
+}
+
+impl<const N: usize> Eq for AutoUniBox<N> {}
+
+/// Hashes the id, and the hosted value if the unibox was created with [`Uniboxed::new_hashable`].
+impl<const N: usize> Hash for AutoUniBox<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        dispatch!(&self.storage, ubox => ubox.hash(state))
+    }
+}
+
+/// Panics if the unibox is not cloneable, see [`CloneUniboxed::try_clone`].
+impl<const N: usize> Clone for AutoUniBox<N> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn clone(&self) -> Self {
+        Self {
+            storage: match &self.storage {
+                Storage::Inline(ubox) => Storage::Inline(ubox.clone()),
+                Storage::Heap(ubox) => Storage::Heap(ubox.clone())
+            },
+            on_drop: self.on_drop
+        }
+    }
+}
+
+/// Empty unibox, see [`Uniboxed::empty`].
+impl<const N: usize> Default for AutoUniBox<N> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> Zeroize for AutoUniBox<N> {
+    fn zeroize(&mut self) {
+        dispatch!(&mut self.storage, ubox => ubox.zeroize())
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> ZeroizeOnDrop for AutoUniBox<N> {}
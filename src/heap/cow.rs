@@ -0,0 +1,97 @@
+use core::{
+    any::TypeId,
+    fmt::{
+        self,
+        Debug
+    },
+    ops::Deref
+};
+use super::{UniArc, UniBox};
+use crate::{CloneUniboxed, TypeMetaOf, Uniboxed, UniBoxError};
+
+/// Clone-on-write unibox, that shares a read-only value among many owners and clones it on the first mutable access.
+///
+/// The value is shared through a [`UniArc`], so cloning a UniCow is cheap and it can be sent to other threads. The clone function of the hosted type is recorded at construction, like with [`CloneUniboxed::new_cloneable`], and used by [`UniCow::downcast_mut`] when the value has other owners. It dereferences to a [`UniBox`] for read access.
+///
+/// ```
+/// use unibox::{UniCow, Uniboxed};
+///
+/// let config = UniCow::new([10u32, 20]).expect("Failed creating UniCow");
+/// let mut edited = config.clone();
+/// edited.downcast_mut::<[u32; 2]>().expect("Failed cloning the value")[0] = 30;
+/// assert_eq!(config.downcast_ref::<[u32; 2]>(), Some(&[10, 20]));
+/// assert_eq!(edited.downcast_ref::<[u32; 2]>(), Some(&[30, 20]));
+/// ```
+#[derive(Clone)]
+pub struct UniCow {
+    shared: UniArc
+}
+
+impl UniCow {
+    /// Create a new UniCow instance, that can be accessed safely with [`Uniboxed::downcast_ref`] and [`UniCow::downcast_mut`].
+    ///
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new<T: Clone + Send + Sync + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            shared: UniArc::from_unibox(UniBox::new_with_meta(instance, &TypeMetaOf::<T>::CLONE_ANY)?)
+        })
+    }
+
+    /// The hosted value has other owners, so a mutable access would clone it.
+    pub fn is_shared(&self) -> bool {
+        UniArc::strong_count(&self.shared) > 1
+    }
+
+    /// Get mutable access to the unibox, cloning the hosted value first if it has other owners.
+    ///
+    /// Returns Err if the clone can't be allocated.
+    ///
+    /// # Safety
+    ///
+    /// The unibox must keep hosting a cloneable, `Send` and `Sync` value, e.g. it can't be swapped with another unibox.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn to_mut(&mut self) -> Result<&mut UniBox, UniBoxError> {
+        let layout = self.layout();
+        if self.is_shared() {
+            // Cloned from a Send and Sync value
+            self.shared = UniArc::from_unibox(self.shared.try_clone().ok_or(UniBoxError::AllocFailed(layout))?);
+        }
+        // The only owner now, and no one else can clone it while borrowed mutably
+        UniArc::get_mut(&mut self.shared).ok_or(UniBoxError::AllocFailed(layout))
+    }
+
+    /// Get mutable reference to the hosted value if it is of type `T`, cloning it first if it has other owners.
+    ///
+    /// Returns None if the type is different or the clone can't be allocated.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if self.hosted_type_id() != Some(TypeId::of::<T>()) {
+            return None;
+        }
+        // Only the value is mutated, not its type
+        unsafe { self.to_mut() }.ok()?.downcast_mut()
+    }
+
+    /// Convert into a unibox, cloning the hosted value if it has other owners.
+    ///
+    /// Returns Err if the clone can't be allocated.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn into_unibox(self) -> Result<UniBox, UniBoxError> {
+        UniArc::try_unwrap(self.shared).or_else(|shared| shared.try_clone().ok_or_else(|| UniBoxError::AllocFailed(shared.layout())))
+    }
+}
+
+impl Deref for UniCow {
+    type Target = UniBox;
+
+    fn deref(&self) -> &UniBox {
+        &self.shared
+    }
+}
+
+impl Debug for UniCow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UniCow").field(&**self).finish()
+    }
+}
@@ -1,26 +1,529 @@
 use core::{
-    mem,
+    convert::TryFrom,
+    any::Any,
+    cmp::Ordering,
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    mem::{
+        self,
+        MaybeUninit
+    },
     alloc::{
         Layout
     },
     ops::Drop,
-    ptr
+    ptr::{
+        self,
+        NonNull
+    }
 };
-use super::super::Uniboxed;
+use super::super::{AccessError, Buffer, CloneUniboxed, DynCast, OnDropHook, TypeMeta, TypeMetaOf, Uniboxed, UniBoxError, UniBoxN};
+use crate::typemeta::TypeMetaDyn;
+use crate::uniboxed::Hosted;
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::LiveToken;
+#[cfg(feature = "stats")]
+use crate::stats::StatsToken;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "bytemuck")]
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "bytemuck")]
+use crate::SharedError;
 extern crate alloc;
+use alloc::boxed::Box;
+
+/// Usual size of a huge page (2 MiB), to be used as alignment in [`UniBox::new_aligned`].
+pub const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Alignment of the allocations made by [`UniBox::from_bytes_with_id`], enough for any primitive type.
+pub const MAX_ALIGN: usize = 16;
+
+/// Converts the unibox into a `Box<dyn Any>`, see [`UniBox::into_any`].
+type IntoAnyFn = fn(&mut UniBox) -> Box<dyn Any>;
+
+/// Everything a [`UniBox`] knows about its allocation and hosted value, except the pointer, returned by [`UniBox::into_raw`].
+pub struct UniBoxMeta {
+    layout: Layout,
+    hosted: Hosted,
+    into_any: Option<IntoAnyFn>
+}
+
+impl UniBoxMeta {
+    /// Layout of the allocation, that may be bigger than the one of the hosted type.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Type identifier of the hosted value.
+    pub fn id(&self) -> &'static str {
+        self.hosted.id
+    }
+}
+
+impl Debug for UniBoxMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniBoxMeta").field("layout", &self.layout).field("id", &self.hosted.id).finish_non_exhaustive()
+    }
+}
 
 /// Store a type on heap.
 pub struct UniBox {
-    buffer: *mut u8,
+    // Never null, so `Option<UniBox>` is as big as a UniBox
+    buffer: NonNull<u8>,
     layout: Layout,
+    // Shared with static uniboxes, so values can move between them
+    type_meta: Option<&'static TypeMeta>,
     id: &'static str,
     len: usize,
-    autodrop: fn(&Self)
+    meta: usize,
+    on_drop: Option<OnDropHook>,
+    into_any: Option<IntoAnyFn>,
+    #[cfg(feature = "diagnostics")]
+    live: LiveToken,
+    #[cfg(feature = "stats")]
+    stats: StatsToken
 }
 
 impl UniBox {
+    /// Create a new UniBox instance that wipes the hosted value on drop.
+    /// 
+    /// Before the hosted destructor runs, [`Zeroize::zeroize`] is called on the value, so secrets owned by it are wiped too.
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroizing<T: Zeroize + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::ZEROIZE)
+    }
+
+    /// Create a new UniBox instance hosting secret material, like keys or passwords, even if it doesn't implement [`Zeroize`].
+    /// 
+    /// The allocation is wiped with volatile writes after the hosted value is dropped, before being freed. The `zeroize` feature does this for every unibox, but this constructor only exists with the feature, so the wipe can't be disabled by accident.
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_secret<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, TypeMeta::of::<T>())
+    }
+
+    /// Create a new UniBox instance, allocating memory with at least *align* alignment.
+    /// 
+    /// The allocation size is rounded up to a multiple of *align*. For very large values, using [`HUGE_PAGE_SIZE`] helps the OS back the allocation with huge pages, reducing TLB pressure.
+    /// 
+    /// Returns Err if *align* is not a power of two or the allocation fails. See [`set_alloc_error_hook`](crate::set_alloc_error_hook) for allocation failures.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_aligned<T: 'static>(instance: T, align: usize) -> Result<Self, UniBoxError> {
+        let layout = Layout::new::<T>().align_to(align).map_err(|_| UniBoxError::InvalidAlign(align))?.pad_to_align();
+        Self::new_with_layout(instance, layout)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_with_layout<T: Sized>(instance: T, layout: Layout) -> Result<Self, UniBoxError> {
+        unsafe {
+            Self::emplace_with_layout(layout, super::allochook::alloc, |slot: &mut MaybeUninit<T>| {
+                slot.write(instance);
+            })
+        }
+    }
+
+    /// Create a new UniBox instance, constructing the value directly inside the allocation.
+    /// 
+    /// Closure *f* gets the uninitialized memory of the value, so big types can be built without copying them from the stack.
+    /// 
+    /// Returns Err if the allocation fails.
+    /// 
+    /// # Safety
+    /// 
+    /// *f* must fully initialize the value.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn new_with<T: Sized>(f: impl FnOnce(&mut MaybeUninit<T>)) -> Result<Self, UniBoxError> {
+        Self::emplace_with_layout(Layout::new::<T>(), super::allochook::alloc, f)
+    }
+
+    /// Create a new UniBox instance hosting a value that can be accessed as a trait object. See [`UniBoxN::new_dyn`].
+    /// 
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_dyn<C: DynCast<T>, T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaDyn::<T, C>::DYN)
+    }
+
+    /// Create a new UniBox instance hosting a zeroed `T`, without copying any value.
+    /// 
+    /// The memory is obtained with [`alloc_zeroed`](alloc::alloc::alloc_zeroed), that for big sizes is usually much faster than zeroing it.
+    /// 
+    /// Returns Err if the allocation fails.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroed<T: Zeroable + 'static>() -> Result<Self, UniBoxError> {
+        unsafe { Self::emplace_with_layout(Layout::new::<T>(), super::allochook::alloc_zeroed, |_: &mut MaybeUninit<T>| {}) }
+    }
+
+    /// Create a new UniBox instance hosting a [`Pod`] value, that is cloned by copying its bytes, without a clone hook.
+    /// 
+    /// Returns Err if the allocation fails.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_pod<T: Pod>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::POD)
+    }
+
+    /// Rebuild a UniBox instance hosting a Pod value written to a shared-memory segment. See [`UniBoxN::from_shared`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_shared<T: Pod>(segment: &[u8]) -> Result<Self, SharedError> {
+        let (instance, meta) = crate::shared::read_shared::<T>(segment)?;
+        Ok(Self::new_pod(instance)?.with_meta(meta))
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    unsafe fn emplace_with_layout<T: Sized>(layout: Layout, alloc: fn(Layout) -> *mut u8, f: impl FnOnce(&mut MaybeUninit<T>)) -> Result<Self, UniBoxError> {
+        // The empty unibox owns the allocation, so it's freed if f panics
+        let mut unibox = Self::with_allocation(layout, alloc)?;
+        f(&mut *(unibox.buffer.as_ptr() as *mut MaybeUninit<T>));
+        unibox.set_hosted(TypeMeta::of::<T>());
+        Ok(unibox)
+    }
+
+    /// Create a new UniBox instance in *buffer*, an allocation of *layout* made by the global allocator that fits `T`.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub(crate) fn from_allocation<T: Sized>(instance: T, buffer: NonNull<u8>, layout: Layout) -> Self {
+        let mut unibox = Self::empty();
+        unibox.store(instance, buffer, layout);
+        unibox
+    }
+
+    /// Drop the hosted value, if any, and hand over the allocation instead of freeing it. None if nothing was allocated.
+    pub(crate) fn into_allocation(mut self) -> Option<(NonNull<u8>, Layout)> {
+        self.drop_value();
+        self.wipe();
+        self.forget();
+        let allocation = (self.buffer, self.layout);
+        // Leave the unibox empty without freeing anything, so dropping it does nothing
+        self.buffer = NonNull::dangling();
+        self.layout = Layout::new::<()>();
+        if allocation.1.size() != 0 { Some(allocation) } else { None }
+    }
+
+    /// Create an empty unibox that owns an allocation of *layout*, made with *alloc*. It's freed on drop, but nothing is hosted yet.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub(crate) fn with_allocation(layout: Layout, alloc: fn(Layout) -> *mut u8) -> Result<Self, UniBoxError> {
+        let mut unibox = Self::empty();
+        unibox.buffer = Self::allocate(layout, alloc).ok_or(UniBoxError::AllocFailed(layout))?;
+        unibox.layout = layout;
+        Ok(unibox)
+    }
+
+    /// Rebuild a UniBox instance from the raw bytes of a value, e.g. received over a serial link.
+    /// 
+    /// The allocation is aligned to [`MAX_ALIGN`] bytes, but the alignment of the type is unknown, see [`Uniboxed::layout`]. The hosted value will never be dropped, and its [`TypeMeta`] has no name and a size of 0.
+    /// 
+    /// Returns Err if *bytes* is empty or the allocation fails.
+    /// 
+    /// # Safety
+    /// 
+    /// Only valid for Pod-like types, that don't implement Drop and don't contain pointers. *bytes* must be a valid image of the type identified by *id*.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn from_bytes_with_id(bytes: &[u8], id: &'static str) -> Result<Self, UniBoxError> {
+        if bytes.is_empty() {
+            return Err(UniBoxError::ZeroSized);
+        }
+        let layout = Layout::from_size_align_unchecked(bytes.len(), MAX_ALIGN);
+        let mut unibox = Self::empty();
+        let buffer = NonNull::new(super::allochook::alloc(layout)).ok_or(UniBoxError::AllocFailed(layout))?;
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_ptr(), bytes.len());
+        unibox.buffer = buffer;
+        unibox.layout = layout;
+        unibox.set_hosted_raw(id, bytes.len(), &TypeMeta::RAW);
+        Ok(unibox)
+    }
+
+    /// Consume the unibox and leak the hosted value, returning a `'static` reference to it, like [`Box::leak`](alloc::boxed::Box::leak).
+    /// 
+    /// The allocation is never freed. With the `diagnostics` feature, the value is reported as live forever.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are valid for `'static`.
+    pub unsafe fn leak<T: Sized>(self) -> &'static mut T {
+        self.integrity_checks::<T>();
+        let buffer = self.buffer.as_ptr() as *mut T;
+        mem::forget(self);
+        &mut *buffer
+    }
+
+    /// Consume the unibox and return the pointer to the hosted value, with the metadata needed to rebuild it with [`UniBox::from_raw`], e.g. to pass it through the context pointer of a C callback.
+    /// 
+    /// The hosted value is not dropped and the allocation is not freed until the unibox is rebuilt.
+    pub fn into_raw(mut self) -> (*mut u8, UniBoxMeta) {
+        let raw = (
+            self.buffer.as_ptr(),
+            UniBoxMeta {
+                layout: self.layout,
+                hosted: self.hosted(),
+                into_any: self.into_any
+            }
+        );
+        // Leave the unibox empty without freeing anything, so dropping it does nothing
+        self.buffer = NonNull::dangling();
+        self.layout = Layout::new::<()>();
+        self.on_drop = None;
+        self.forget();
+        raw
+    }
+
+    /// Rebuild a unibox from the parts returned by [`UniBox::into_raw`], with the same id, metadata and drop behavior.
+    /// 
+    /// # Safety
+    /// 
+    /// *ptr* and *meta* must come from the same call to [`UniBox::into_raw`], and the unibox can only be rebuilt once.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn from_raw(ptr: *mut u8, meta: UniBoxMeta) -> Self {
+        let mut unibox = Self::empty();
+        unibox.buffer = NonNull::new_unchecked(ptr);
+        unibox.layout = meta.layout;
+        unibox.set_hosted_from(meta.hosted);
+        unibox.into_any = meta.into_any;
+        unibox
+    }
+
+    /// Convert into a `Box<dyn Any>`, reusing the allocation when possible.
+    /// 
+    /// Returns Err with the unibox if it was not created with [`Uniboxed::new_any`].
+    #[allow(clippy::result_large_err)]
+    pub fn into_any(mut self) -> Result<Box<dyn Any>, Self> {
+        match self.into_any {
+            Some(into_any) => Ok(into_any(&mut self)),
+            None => Err(self)
+        }
+    }
+
+    /// Create a new UniBox instance from a Box, taking over its allocation instead of copying the value.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_box<T: 'static>(instance: Box<T>) -> Self {
+        let mut unibox = Self::empty();
+        unibox.buffer = NonNull::from(Box::leak(instance)).cast();
+        unibox.layout = Layout::new::<T>();
+        unibox.set_hosted(TypeMeta::of::<T>());
+        unibox
+    }
+
+    /// Create a new UniBox instance from a `Box<dyn Any>` hosting a `T`, taking over its allocation.
+    /// 
+    /// The unibox records the [`TypeId`](core::any::TypeId), like [`Uniboxed::new_any`], so it can be converted back with [`UniBox::into_any`].
+    /// 
+    /// Returns Err with the box if it doesn't host a `T`.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_any<T: 'static>(instance: Box<dyn Any>) -> Result<Self, Box<dyn Any>> {
+        let mut unibox = Self::from_box(instance.downcast::<T>()?);
+        unibox.type_meta = Some(&TypeMetaOf::<T>::ANY);
+        unibox.into_any = Some(Self::box_any::<T>);
+        Ok(unibox)
+    }
+
+    /// Move the hosted value into a Box, handing over the allocation, like [`Uniboxed::into_inner`] without copying the value.
+    /// 
+    /// If the allocation doesn't have the layout of `T`, e.g. the unibox was created with [`UniBox::new_aligned`], the value is copied into a new Box.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn into_box<T: Sized>(mut self) -> Box<T> {
+        self.integrity_checks::<T>();
+        self.take_box()
+    }
+
+    /// Move the hosted value, with its id, metadata and hooks, to a static unibox, like [`UniBox128`](crate::UniBox128).
+    /// 
+    /// The conversion back to a `Box<dyn Any>` is lost, see [`UniBox::into_any`].
+    /// 
+    /// Returns Err with the unibox if the hosted value doesn't fit.
+    #[allow(clippy::result_large_err)]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn try_to_static<U: TryFrom<UniBox, Error = UniBox>>(self) -> Result<U, Self> {
+        U::try_from(self)
+    }
+
+    /// Put a new value in the unibox, reusing the allocation if it's big and aligned enough for `T`, even if the hosted type is different.
+    /// 
+    /// The hosted value, if any, is dropped. Unlike [`Uniboxed::replace`], changing the hosted type doesn't free and allocate memory when the new one fits, e.g. in a hot slot that stores different states. The new value gets an id assigned with [`core::any::type_name`].
+    /// 
+    /// Returns Err with the new value if a new allocation is needed and it fails. The hosted value is kept in that case.
+    pub fn replace_with<T: 'static>(&mut self, instance: T) -> Result<(), T> {
+        let layout = Layout::new::<T>();
+        if layout.size() == 0 || layout.size() > self.layout.size() || layout.align() > self.layout.align() {
+            return unsafe { self.replace(instance) }.map(|_| ());
+        }
+        self.drop_value();
+        self.wipe();
+        self.forget();
+        unsafe {
+            ptr::write(self.buffer.as_ptr() as *mut T, instance);
+        }
+        self.set_hosted(TypeMeta::of::<T>());
+        Ok(())
+    }
+
+    /// Create a new UniBox instance described by *type_meta*, the metadata of `T` recorded by a constructor.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub(crate) fn new_with_meta<T: 'static>(instance: T, type_meta: &'static TypeMeta) -> Result<Self, UniBoxError> {
+        let mut unibox = Self::new(instance)?;
+        unibox.type_meta = Some(type_meta);
+        Ok(unibox)
+    }
+
+    /// Optional hook of the hosted type, if any.
+    fn hook<F>(&self, f: impl FnOnce(&'static TypeMeta) -> Option<F>) -> Option<F> {
+        self.type_meta.and_then(f)
+    }
+
+    /// The hosted value, with its id and metadata.
+    fn hosted(&self) -> Hosted {
+        Hosted {
+            id: self.id,
+            len: self.len,
+            type_meta: self.type_meta,
+            meta: self.meta,
+            on_drop: self.on_drop
+        }
+    }
+
+    /// Record that the allocation, of an empty unibox, hosts the value described by *hosted*.
+    fn set_hosted_from(&mut self, hosted: Hosted) {
+        if let Some(type_meta) = hosted.type_meta {
+            self.set_hosted_raw(hosted.id, hosted.len, type_meta);
+        }
+        self.meta = hosted.meta;
+        self.on_drop = hosted.on_drop;
+    }
+
+    /// Hook recorded by the uniboxes that know their type is `T`, used by [`UniBox::into_any`].
+    fn box_any<T: 'static>(&mut self) -> Box<dyn Any> {
+        unsafe { self.take_box::<T>() }
+    }
+
+    /// Move the hosted `T` into a Box, leaving the unibox empty. The allocation is handed over if its layout is the one of `T`, otherwise the value is copied into a new Box.
+    unsafe fn take_box<T: Sized>(&mut self) -> Box<T> {
+        if self.layout == Layout::new::<T>() {
+            let instance = Box::from_raw(self.buffer.as_ptr() as *mut T);
+            // The Box owns the allocation now
+            self.layout = Layout::new::<()>();
+            self.clear();
+            instance
+        }
+        else {
+            let instance = self.as_owned::<T>();
+            self.clear();
+            Box::new(instance)
+        }
+    }
+
+    /// Allocate memory for *layout* with *alloc*, None if it fails. Zero-sized layouts get a dangling pointer with the right alignment, no memory is allocated.
+    fn allocate(layout: Layout, alloc: fn(Layout) -> *mut u8) -> Option<NonNull<u8>> {
+        if layout.size() == 0 {
+            NonNull::new(layout.align() as *mut u8)
+        }
+        else {
+            NonNull::new(alloc(layout))
+        }
+    }
+
+    /// Store a value in the unibox, that must be empty, using *buffer*, an allocation of *layout* that fits it.
+    fn store<T: Sized>(&mut self, instance: T, buffer: NonNull<u8>, layout: Layout) {
+        unsafe {
+            ptr::write(buffer.as_ptr() as *mut T, instance);
+        }
+        self.buffer = buffer;
+        self.layout = layout;
+        self.set_hosted(TypeMeta::of::<T>());
+    }
+
+    /// Record that the allocation hosts a value of the type described by *type_meta*.
+    pub(crate) fn set_hosted(&mut self, type_meta: &'static TypeMeta) {
+        self.set_hosted_raw(type_meta.name(), type_meta.size, type_meta);
+    }
+
+    /// Record that the allocation hosts *len* bytes of a value identified by *id*, of the type described by *type_meta*.
+    fn set_hosted_raw(&mut self, id: &'static str, len: usize, type_meta: &'static TypeMeta) {
+        self.type_meta = Some(type_meta);
+        self.id = id;
+        self.len = len;
+        #[cfg(feature = "diagnostics")]
+        self.live.set(id, len);
+        #[cfg(feature = "stats")]
+        self.stats.set(id, len);
+    }
+
+    /// Drop the hosted value, if any, leaving the unibox empty.
+    fn drop_hosted(&mut self) {
+        self.drop_value();
+        self.clear();
+    }
+
+    /// Drop the hosted value, if any, keeping the allocation. The unibox must be reset afterwards.
+    fn drop_value(&mut self) {
+        if let Some(type_meta) = self.type_meta {
+            if let Some(on_drop) = self.on_drop {
+                on_drop(self);
+            }
+            #[cfg(feature = "zeroize")]
+            self.zeroize();
+            if let Some(drop_fn) = type_meta.drop_fn {
+                drop_fn(self.buffer.as_ptr());
+            }
+        }
+    }
+
+    /// Free the allocation and reset to the empty state, once the hosted value has been dropped or moved out.
+    fn clear(&mut self) {
+        self.wipe();
+        if self.layout.size() != 0 {
+            unsafe { alloc::alloc::dealloc(self.buffer.as_ptr(), self.layout) };
+        }
+        self.buffer = NonNull::dangling();
+        self.layout = Layout::new::<()>();
+        self.forget();
+    }
+
+    /// Wipe the allocation, once the hosted value has been dropped or moved out, with the `zeroize` and poison features.
+    fn wipe(&mut self) {
+        #[cfg(feature = "zeroize")]
+        unsafe {
+            core::slice::from_raw_parts_mut(self.buffer.as_ptr(), self.layout.size()).zeroize();
+        }
+        #[cfg(feature = "poison")]
+        for i in 0..self.layout.size() {
+            unsafe { ptr::write_volatile(self.buffer.as_ptr().add(i), 0) };
+        }
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        unsafe {
+            ptr::write_bytes(self.buffer.as_ptr(), crate::uniboxed::POISON_BYTE, self.layout.size());
+        }
+    }
+
+    /// Reset to the empty state, keeping the allocation.
+    fn forget(&mut self) {
+        self.type_meta = None;
+        self.id = "";
+        self.len = 0;
+        self.meta = 0;
+        self.into_any = None;
+        #[cfg(feature = "diagnostics")]
+        self.live.set("", 0);
+        #[cfg(feature = "stats")]
+        self.stats.set("", 0);
+    }
+
     unsafe fn as_owned<T: Sized>(&self) -> T {
-        ptr::read(self.buffer as *const T)
+        ptr::read(self.buffer.as_ptr() as *const T)
     }
 
     fn integrity_checks<T>(&self) {
@@ -31,55 +534,336 @@ impl UniBox {
 }
 
 impl Uniboxed for UniBox {
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized {
-        let autodrop = |_self: &Self| {
-            mem::drop(unsafe { _self.as_owned::<T>() });
-        };
-        let layout = Layout::new::<T>();
-        let buffer = unsafe { alloc::alloc::alloc(layout) };
-        if buffer.is_null() {
-            return Err(());
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    unsafe fn new_unbounded<T: Sized>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Self::new_with_layout(instance, Layout::new::<T>())
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        let mut unibox = Self::new_with_meta(instance, &TypeMetaOf::<T>::ANY)?;
+        unibox.into_any = Some(Self::box_any::<T>);
+        Ok(unibox)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_eq<T: PartialEq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::EQ)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_hashable<T: Hash + Eq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::HASH)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_ord<T: Ord + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::ORD)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_debuggable<T: Debug + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::DEBUG)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn empty() -> Self {
+        Self {
+            buffer: NonNull::dangling(),
+            layout: Layout::new::<()>(),
+            type_meta: None,
+            id: "",
+            len: 0,
+            meta: 0,
+            on_drop: None,
+            into_any: None,
+            #[cfg(feature = "diagnostics")]
+            live: LiveToken::new("", 0),
+            #[cfg(feature = "stats")]
+            stats: StatsToken::new()
         }
-        let src = &instance as *const T;
-        unsafe {
-            core::ptr::copy(src, buffer as *mut T, 1)
-        };
-        mem::forget(instance);
-        Ok(
-            Self {
-                buffer,
-                layout,
-                id: core::any::type_name::<T>(),
-                len: mem::size_of::<T>(),
-                autodrop
-            }
-        )
     }
 
     unsafe fn as_ref<T: Sized>(&self) -> &T {
         self.integrity_checks::<T>();
-        mem::transmute::<*mut u8, &T>(self.buffer)
+        &*(self.buffer.as_ptr() as *const T)
     }
 
     unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
         self.integrity_checks::<T>();
-        mem::transmute::<*mut u8, &mut T>(self.buffer)
+        &mut *(self.buffer.as_ptr() as *mut T)
+    }
+
+    unsafe fn take<T: Sized>(&mut self) -> T {
+        self.integrity_checks::<T>();
+        let instance = self.as_owned::<T>();
+        self.clear();
+        instance
+    }
+
+    unsafe fn try_take<T: Sized>(&mut self) -> Result<T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id, self.len)?;
+        let instance = self.as_owned::<T>();
+        self.clear();
+        Ok(instance)
+    }
+
+    fn forget_inner(mut self) {
+        self.clear();
+    }
+
+    unsafe fn replace<T: Sized>(&mut self, instance: T) -> Result<Option<T>, T> {
+        if !self.is_empty() && self.check_type::<T>() {
+            Ok(Some(mem::replace(self.as_mut_ref::<T>(), instance)))
+        }
+        else {
+            let layout = Layout::new::<T>();
+            let buffer = match Self::allocate(layout, super::allochook::alloc) {
+                Some(buffer) => buffer,
+                None => return Err(instance)
+            };
+            self.drop_hosted();
+            self.store(instance, buffer, layout);
+            Ok(None)
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.type_meta.is_none()
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        self.buffer.as_ptr()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_ptr()
     }
 
     fn len(&self) -> usize {
         self.len
     }
 
+    fn layout(&self) -> Layout {
+        let align = self.type_meta.map_or(1, TypeMeta::align);
+        unsafe { Layout::from_size_align_unchecked(self.len, align) }
+    }
+
     fn id(&self) -> &'static str {
         self.id
     }
+
+    fn set_id(&mut self, id: &'static str) {
+        if !self.is_empty() {
+            self.id = id;
+            #[cfg(feature = "diagnostics")]
+            self.live.set(id, self.len);
+            #[cfg(feature = "stats")]
+            self.stats.set(id, self.len);
+        }
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        self.type_meta
+    }
+
+    fn meta(&self) -> usize {
+        self.meta
+    }
+
+    fn set_meta(&mut self, meta: usize) {
+        self.meta = meta;
+    }
+
+    fn set_on_drop(&mut self, hook: OnDropHook) {
+        self.on_drop = Some(hook);
+    }
+
+    fn cmp_dyn(&self, other: &Self) -> Ordering {
+        if self.is_empty() || other.is_empty() {
+            return other.is_empty().cmp(&self.is_empty());
+        }
+        self.id.cmp(other.id).then(self.len.cmp(&other.len)).then_with(|| {
+            // Ids can be changed, the hook is only called if both values are of the same type and recorded it
+            let cmp = |unibox: &Self| unibox.hook(|type_meta| type_meta.cmp);
+            self.hosted_type_id().cmp(&other.hosted_type_id())
+                .then(cmp(self).is_some().cmp(&cmp(other).is_some()))
+                .then_with(|| cmp(self).map_or(Ordering::Equal, |cmp| cmp(self.as_ptr(), other.as_ptr())))
+        })
+    }
+
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        self.type_meta?.debug.map(|_| self as &dyn Debug)
+    }
+
+    fn eq_dyn(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return self.is_empty() && other.is_empty();
+        }
+        let eq = |unibox: &Self| unibox.hook(|type_meta| type_meta.eq);
+        let hashable = |unibox: &Self| unibox.hook(|type_meta| type_meta.hash).is_some();
+        // Without a hook on both sides a unibox is only equal to itself, and values that hash differently are never equal
+        core::ptr::eq(self, other) || match (eq(self), eq(other)) {
+            // Ids can be changed, only the TypeId proves that both values are of the type expected by the hook
+            (Some(eq), Some(_)) => self.id == other.id && self.hosted_type_id() == other.hosted_type_id() && hashable(self) == hashable(other) && eq(self.as_ptr(), other.as_ptr()),
+            _ => false
+        }
+    }
+}
+
+impl CloneUniboxed for UniBox {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_cloneable<T: Clone + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::CLONE)
+    }
+
+    fn is_cloneable(&self) -> bool {
+        self.type_meta.is_none_or(TypeMeta::is_cloneable)
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn try_clone(&self) -> Option<Self> {
+        let mut unibox = Self::empty();
+        if let Some(type_meta) = self.type_meta.filter(|type_meta| type_meta.is_cloneable()) {
+            let buffer = Self::allocate(self.layout, super::allochook::alloc)?;
+            // The empty unibox owns the allocation, so it's freed if clone panics
+            unibox.buffer = buffer;
+            unibox.layout = self.layout;
+            match type_meta.clone {
+                Some(clone) => clone(self.as_ptr(), buffer.as_ptr()),
+                // Pod values are copied bytewise
+                None => unsafe { ptr::copy_nonoverlapping(self.as_ptr(), buffer.as_ptr(), self.len) }
+            }
+            unibox.set_hosted_raw(self.id, self.len, type_meta);
+            unibox.meta = self.meta;
+            unibox.into_any = self.into_any;
+        }
+        else if !self.is_empty() {
+            return None;
+        }
+        unibox.on_drop = self.on_drop;
+        Some(unibox)
+    }
+}
+
+/// Prints the hosted value if the unibox was created with [`Uniboxed::new_debuggable`], otherwise its id and length.
+impl Debug for UniBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hook(|type_meta| type_meta.debug) {
+            Some(debug) => debug(self.as_ptr(), f),
+            None => f.debug_struct("UniBox").field("id", &self.id).field("len", &self.len).finish_non_exhaustive()
+        }
+    }
+}
+
+/// Uses [`Uniboxed::eq_dyn`]. Uniboxes not created with [`Uniboxed::new_eq`] or [`Uniboxed::new_hashable`] are only equal to themselves.
+impl PartialEq for UniBox {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_dyn(other)
+    }
+}
+
+impl Eq for UniBox {}
+
+/// Hashes the id, and the hosted value if the unibox was created with [`Uniboxed::new_hashable`].
+impl Hash for UniBox {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        if let Some(hash) = self.hook(|type_meta| type_meta.hash) {
+            hash(self.as_ptr(), state);
+        }
+    }
+}
+
+/// Panics if the unibox is not cloneable, see [`CloneUniboxed::try_clone`].
+impl Clone for UniBox {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn clone(&self) -> Self {
+        if !self.is_cloneable() {
+            panic!("Hosted value is not cloneable");
+        }
+        self.try_clone().unwrap_or_else(|| alloc::alloc::handle_alloc_error(self.layout))
+    }
+}
+
+/// Empty unibox, see [`Uniboxed::empty`].
+impl Default for UniBox {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<B: Buffer> UniBoxN<B> {
+    /// Move the hosted value, with its id, metadata and hooks, to a heap unibox.
+    /// 
+    /// Values hosted with [`UniBoxN::new_or_boxed`] keep their allocation, others are copied into a new one.
+    /// 
+    /// Returns Err with the unibox if the allocation fails.
+    #[allow(clippy::result_large_err, clippy::wrong_self_convention)]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn to_heap(mut self) -> Result<UniBox, Self> {
+        let layout = self.layout();
+        let hosted = self.hosted();
+        let mut unibox = UniBox::empty();
+        if self.is_boxed() {
+            // Boxed values are never null
+            unibox.buffer = unsafe { NonNull::new_unchecked(self.as_mut_ptr()) };
+        }
+        else {
+            let buffer = match UniBox::allocate(layout, super::allochook::alloc) {
+                Some(buffer) => buffer,
+                None => return Err(self)
+            };
+            unsafe {
+                ptr::copy_nonoverlapping(self.as_ptr(), buffer.as_ptr(), hosted.len);
+            }
+            unibox.buffer = buffer;
+        }
+        unibox.layout = layout;
+        unibox.set_hosted_from(hosted);
+        // The value was moved out
+        self.clear();
+        Ok(unibox)
+    }
+}
+
+/// Moves the hosted value to a static unibox, see [`UniBox::try_to_static`]. Returns Err with the unibox if it doesn't fit.
+impl<B: Buffer> TryFrom<UniBox> for UniBoxN<B> {
+    type Error = UniBox;
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn try_from(mut unibox: UniBox) -> Result<Self, UniBox> {
+        if unibox.len > Self::capacity() || unibox.layout().align() > Self::max_align() {
+            return Err(unibox);
+        }
+        let mut static_unibox = Self::empty();
+        unsafe {
+            ptr::copy_nonoverlapping(unibox.as_ptr(), static_unibox.as_mut_ptr(), unibox.len);
+        }
+        static_unibox.set_hosted_from(unibox.hosted());
+        // The value was moved out
+        unibox.clear();
+        Ok(static_unibox)
+    }
 }
 
 impl Drop for UniBox {
     fn drop(&mut self) {
-        (self.autodrop)(self);
-        unsafe {
-            alloc::alloc::dealloc(self.buffer, self.layout);
+        self.drop_hosted();
+    }
+}
+
+/// Wipes the hosted value in place if the unibox was created with [`UniBox::new_zeroizing`], otherwise it does nothing.
+#[cfg(feature = "zeroize")]
+impl Zeroize for UniBox {
+    fn zeroize(&mut self) {
+        if let Some(zeroize) = self.hook(|type_meta| type_meta.zeroize) {
+            zeroize(self.as_mut_ptr());
         }
     }
 }
+
+/// The allocation is always wiped after the hosted value is dropped or moved out.
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for UniBox {}
@@ -1,2 +1,32 @@
 mod dynamicbox;
-pub use dynamicbox::*;
\ No newline at end of file
+pub use dynamicbox::*;
+
+mod allochook;
+pub use allochook::*;
+
+mod pinned;
+pub use pinned::*;
+
+mod uninit;
+pub use uninit::*;
+
+mod pool;
+pub use pool::*;
+
+mod arena;
+pub use arena::*;
+
+mod slotmap;
+pub use slotmap::*;
+
+mod rc;
+pub use rc::*;
+
+mod arc;
+pub use arc::*;
+
+mod cow;
+pub use cow::*;
+
+mod autobox;
+pub use autobox::*;
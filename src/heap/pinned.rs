@@ -0,0 +1,53 @@
+use core::{
+    ops::Deref,
+    pin::Pin
+};
+use super::UniBox;
+use crate::{Uniboxed, UniBoxError};
+
+/// Heap unibox whose hosted value is pinned, created with [`UniBox::pin_new`].
+/// 
+/// The value is never moved out of its allocation: it can't be taken or replaced, only accessed through pinned references and dropped along with the unibox. Moving the `UniBoxPin` itself is fine.
+pub struct UniBoxPin {
+    unibox: UniBox
+}
+
+impl UniBox {
+    /// Create a new unibox whose hosted value can't be moved after construction, for `!Unpin` types like futures.
+    /// 
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn pin_new<T: 'static>(instance: T) -> Result<UniBoxPin, UniBoxError> {
+        Ok(UniBoxPin {
+            unibox: UniBox::new(instance)?
+        })
+    }
+}
+
+impl UniBoxPin {
+    /// Get pinned reference to stored data using a type.
+    /// 
+    /// # Safety
+    /// 
+    /// See [`Uniboxed::as_ref`].
+    pub unsafe fn as_pin<T: Sized>(&self) -> Pin<&T> {
+        Pin::new_unchecked(self.unibox.as_ref())
+    }
+
+    /// Get pinned mutable reference to stored data using a type, e.g. to poll a hosted future.
+    /// 
+    /// # Safety
+    /// 
+    /// See [`Uniboxed::as_mut_ref`].
+    pub unsafe fn as_pin_mut<T: Sized>(&mut self) -> Pin<&mut T> {
+        Pin::new_unchecked(self.unibox.as_mut_ref())
+    }
+}
+
+impl Deref for UniBoxPin {
+    type Target = UniBox;
+
+    fn deref(&self) -> &UniBox {
+        &self.unibox
+    }
+}
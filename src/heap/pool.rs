@@ -0,0 +1,134 @@
+use core::{
+    alloc::Layout,
+    cell::RefCell,
+    mem::ManuallyDrop,
+    ops::{
+        Deref,
+        DerefMut,
+        Drop
+    },
+    ptr::NonNull
+};
+use super::UniBox;
+use crate::{Uniboxed, UniBoxError};
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// Pool of heap allocations, grouped by layout, recycled by the uniboxes it creates.
+/// 
+/// [`UniPool::new_box`] hosts a value in a free allocation of the same layout, if any, and the [`UniBoxPooled`] gives it back to the pool when dropped, instead of freeing it. Useful in loops that box and unbox many values, to stop hammering the global allocator.
+/// 
+/// Free allocations are released when the pool is dropped or with [`UniPool::shrink`].
+pub struct UniPool {
+    free: RefCell<Vec<(Layout, Vec<NonNull<u8>>)>>
+}
+
+impl UniPool {
+    /// Create an empty pool.
+    pub const fn new() -> Self {
+        Self {
+            free: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Create a new unibox, reusing a free allocation of the layout of `T` if there is one.
+    /// 
+    /// Returns Err if a new allocation is needed and it fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_box<T: 'static>(&self, instance: T) -> Result<UniBoxPooled<'_>, UniBoxError> {
+        let layout = Layout::new::<T>();
+        let unibox = match self.take(layout) {
+            Some(buffer) => UniBox::from_allocation(instance, buffer, layout),
+            None => UniBox::new(instance)?
+        };
+        Ok(UniBoxPooled {
+            unibox: ManuallyDrop::new(unibox),
+            pool: self
+        })
+    }
+
+    /// Number of free allocations.
+    pub fn len(&self) -> usize {
+        self.free.borrow().iter().map(|(_, buffers)| buffers.len()).sum()
+    }
+
+    /// The pool has no free allocations.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Free all the allocations held by the pool. Those in use are not affected.
+    pub fn shrink(&self) {
+        for (layout, buffers) in self.free.borrow_mut().drain(..) {
+            for buffer in buffers {
+                unsafe { alloc::alloc::dealloc(buffer.as_ptr(), layout) };
+            }
+        }
+    }
+
+    /// Remove a free allocation of *layout* from the pool.
+    fn take(&self, layout: Layout) -> Option<NonNull<u8>> {
+        self.free.borrow_mut().iter_mut().find(|(free, _)| *free == layout).and_then(|(_, buffers)| buffers.pop())
+    }
+
+    /// Give an allocation of *layout* back to the pool.
+    fn give(&self, buffer: NonNull<u8>, layout: Layout) {
+        let mut free = self.free.borrow_mut();
+        match free.iter_mut().find(|(free, _)| *free == layout) {
+            Some((_, buffers)) => buffers.push(buffer),
+            None => free.push((layout, alloc::vec![buffer]))
+        }
+    }
+}
+
+impl Default for UniPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for UniPool {
+    fn drop(&mut self) {
+        self.shrink();
+    }
+}
+
+/// Heap unibox created by a [`UniPool`], that gives its allocation back to the pool when dropped.
+/// 
+/// It dereferences to a [`UniBox`], so it's used like one. If the hosted value is replaced by a different type, the new allocation is the one given back.
+pub struct UniBoxPooled<'a> {
+    unibox: ManuallyDrop<UniBox>,
+    pool: &'a UniPool
+}
+
+impl UniBoxPooled<'_> {
+    /// Detach the unibox from the pool, so its allocation is freed when dropped.
+    pub fn into_unibox(mut self) -> UniBox {
+        let unibox = unsafe { ManuallyDrop::take(&mut self.unibox) };
+        core::mem::forget(self);
+        unibox
+    }
+}
+
+impl Deref for UniBoxPooled<'_> {
+    type Target = UniBox;
+
+    fn deref(&self) -> &UniBox {
+        &self.unibox
+    }
+}
+
+impl DerefMut for UniBoxPooled<'_> {
+    fn deref_mut(&mut self) -> &mut UniBox {
+        &mut self.unibox
+    }
+}
+
+impl Drop for UniBoxPooled<'_> {
+    fn drop(&mut self) {
+        let unibox = unsafe { ManuallyDrop::take(&mut self.unibox) };
+        if let Some((buffer, layout)) = unibox.into_allocation() {
+            self.pool.give(buffer, layout);
+        }
+    }
+}
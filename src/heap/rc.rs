@@ -0,0 +1,91 @@
+use core::{
+    fmt::{
+        self,
+        Debug
+    },
+    ops::Deref
+};
+use super::UniBox;
+use crate::{Uniboxed, UniBoxError};
+extern crate alloc;
+use alloc::rc::Rc;
+
+/// Reference-counted unibox, to share a hosted value among many owners in a single thread, like an [`Rc`].
+///
+/// It dereferences to a [`UniBox`], so the hosted value is accessed like in one, but only immutably. Cloning it only increments a non-atomic counter, and the value is dropped with the last clone.
+///
+/// Like [`Rc`], the associated functions are called as `UniRc::strong_count(&rc)`, so they don't shadow methods of the unibox.
+///
+/// ```
+/// use unibox::{UniRc, Uniboxed};
+///
+/// let config = UniRc::new_any([10u32, 20]).expect("Failed creating UniRc");
+/// let shared = config.clone();
+/// assert_eq!(UniRc::strong_count(&config), 2);
+/// assert_eq!(shared.downcast_ref::<[u32; 2]>(), Some(&[10, 20]));
+/// ```
+#[derive(Clone)]
+pub struct UniRc {
+    unibox: Rc<UniBox>
+}
+
+impl UniRc {
+    /// Create a new UniRc instance. See [`UniBox::new`](crate::Uniboxed::new).
+    ///
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self::from(UniBox::new(instance)?))
+    }
+
+    /// Create a new UniRc instance that can be accessed safely with [`Uniboxed::downcast_ref`]. See [`UniBox::new_any`](crate::Uniboxed::new_any).
+    ///
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self::from(UniBox::new_any(instance)?))
+    }
+
+    /// Number of owners of the hosted value.
+    pub fn strong_count(this: &Self) -> usize {
+        Rc::strong_count(&this.unibox)
+    }
+
+    /// Both point to the same hosted value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Rc::ptr_eq(&this.unibox, &other.unibox)
+    }
+
+    /// Get mutable access to the unibox if there are no other owners.
+    pub fn get_mut(this: &mut Self) -> Option<&mut UniBox> {
+        Rc::get_mut(&mut this.unibox)
+    }
+
+    /// Get the unibox back if there are no other owners. Otherwise returns Err with the UniRc.
+    pub fn try_unwrap(this: Self) -> Result<UniBox, Self> {
+        Rc::try_unwrap(this.unibox).map_err(|unibox| Self { unibox })
+    }
+}
+
+impl From<UniBox> for UniRc {
+    /// Share a unibox, moving it to a reference-counted allocation. The hosted value is not moved.
+    fn from(unibox: UniBox) -> Self {
+        Self {
+            unibox: Rc::new(unibox)
+        }
+    }
+}
+
+impl Deref for UniRc {
+    type Target = UniBox;
+
+    fn deref(&self) -> &UniBox {
+        &self.unibox
+    }
+}
+
+impl Debug for UniRc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UniRc").field(&*self.unibox).finish()
+    }
+}
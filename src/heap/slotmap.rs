@@ -0,0 +1,180 @@
+use core::convert::TryFrom;
+use super::UniBox;
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// Key of a unibox stored in a [`UniSlotMap`].
+///
+/// Besides the slot, it holds the generation of the slot when the unibox was inserted. Removing the unibox bumps the generation, so the key of a removed unibox gives None even after the slot is reused by another one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32
+}
+
+impl SlotKey {
+    /// Position of the slot in the map.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Generation of the slot when the unibox was inserted.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<U> {
+    generation: u32,
+    unibox: Option<U>
+}
+
+/// Collection of uniboxes behind generational keys, for entity/component style code.
+///
+/// Inserting a unibox returns a [`SlotKey`], and the slots of removed uniboxes are reused by later insertions. A key of a removed unibox is detected as stale, instead of giving the unibox that now lives in its slot.
+///
+/// It stores [`UniBox`] by default, but any kind of unibox can be used, like a static one to keep the values inline.
+///
+/// ```
+/// use unibox::{UniSlotMap, UniBox, Uniboxed};
+///
+/// let mut map = UniSlotMap::new();
+/// let key = map.insert(UniBox::new_any(10u64).expect("Failed creating UniBox"));
+/// assert_eq!(map.get(key).and_then(|ubox| ubox.downcast_ref()), Some(&10u64));
+/// map.remove(key);
+/// let other = map.insert(UniBox::new_any(20u64).expect("Failed creating UniBox"));
+/// assert_eq!(other.index(), key.index());
+/// assert!(map.get(key).is_none());
+/// ```
+pub struct UniSlotMap<U = UniBox> {
+    slots: Vec<Slot<U>>,
+    free: Vec<u32>,
+    len: usize
+}
+
+impl<U> UniSlotMap<U> {
+    /// Create an empty map.
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0
+        }
+    }
+
+    /// Create an empty map with room for *capacity* uniboxes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0
+        }
+    }
+
+    /// Store a unibox, and return its key.
+    ///
+    /// # Panics
+    ///
+    /// If the map already has more than `u32::MAX` slots.
+    pub fn insert(&mut self, unibox: U) -> SlotKey {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.unibox = Some(unibox);
+            return SlotKey {
+                index,
+                generation: slot.generation
+            };
+        }
+        let index = u32::try_from(self.slots.len()).expect("Too many slots");
+        self.slots.push(Slot {
+            generation: 0,
+            unibox: Some(unibox)
+        });
+        SlotKey {
+            index,
+            generation: 0
+        }
+    }
+
+    /// Get reference to the unibox of *key*. None if it was removed.
+    pub fn get(&self, key: SlotKey) -> Option<&U> {
+        match self.slots.get(key.index as usize) {
+            Some(slot) if slot.generation == key.generation => slot.unibox.as_ref(),
+            _ => None
+        }
+    }
+
+    /// Get mutable reference to the unibox of *key*. None if it was removed.
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut U> {
+        match self.slots.get_mut(key.index as usize) {
+            Some(slot) if slot.generation == key.generation => slot.unibox.as_mut(),
+            _ => None
+        }
+    }
+
+    /// The unibox of *key* is in the map.
+    pub fn contains_key(&self, key: SlotKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove the unibox of *key* and return it. None if it was already removed.
+    pub fn remove(&mut self, key: SlotKey) -> Option<U> {
+        let slot = match self.slots.get_mut(key.index as usize) {
+            Some(slot) if slot.generation == key.generation => slot,
+            _ => return None
+        };
+        let unibox = slot.unibox.take()?;
+        self.len -= 1;
+        // A slot whose generation would wrap is retired, so old keys can't match it again
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(key.index);
+        }
+        Some(unibox)
+    }
+
+    /// Number of uniboxes in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The map has no uniboxes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all the uniboxes. Their keys are no longer valid.
+    pub fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            let slot = &self.slots[index];
+            if slot.unibox.is_some() {
+                self.remove(SlotKey {
+                    index: index as u32,
+                    generation: slot.generation
+                });
+            }
+        }
+    }
+
+    /// Iterate over the keys and uniboxes in the map.
+    pub fn iter(&self) -> impl Iterator<Item = (SlotKey, &U)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.unibox.as_ref().map(|unibox| (SlotKey { index: index as u32, generation: slot.generation }, unibox))
+        })
+    }
+
+    /// Iterate over the keys and mutable uniboxes in the map.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (SlotKey, &mut U)> + '_ {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            slot.unibox.as_mut().map(|unibox| (SlotKey { index: index as u32, generation }, unibox))
+        })
+    }
+}
+
+impl<U> Default for UniSlotMap<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,51 @@
+use core::{
+    alloc::Layout,
+    marker::PhantomData,
+    mem::MaybeUninit
+};
+use super::UniBox;
+use crate::{TypeMeta, Uniboxed, UniBoxError};
+
+/// Heap unibox whose `T` is not initialized yet, created with [`UniBox::new_uninit`].
+/// 
+/// The value is written in place through [`UniBoxUninit::as_mut_ptr`] or [`UniBoxUninit::as_uninit_mut`], then [`UniBoxUninit::assume_init`] turns it into a regular [`UniBox`]. If dropped before that, the allocation is freed but no destructor runs.
+pub struct UniBoxUninit<T> {
+    // Empty unibox that owns the allocation
+    unibox: UniBox,
+    _type: PhantomData<T>
+}
+
+impl UniBox {
+    /// Allocate memory for a `T` without initializing it, so multi-kilobyte values can be filled in place, e.g. from a file read, without ever being on the stack.
+    /// 
+    /// Returns Err if the allocation fails.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_uninit<T: Sized>() -> Result<UniBoxUninit<T>, UniBoxError> {
+        Ok(UniBoxUninit {
+            unibox: UniBox::with_allocation(Layout::new::<T>(), super::allochook::alloc)?,
+            _type: PhantomData
+        })
+    }
+}
+
+impl<T: Sized> UniBoxUninit<T> {
+    /// Raw mutable pointer to the uninitialized value.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.unibox.as_mut_ptr() as *mut T
+    }
+
+    /// Mutable reference to the uninitialized value.
+    pub fn as_uninit_mut(&mut self) -> &mut MaybeUninit<T> {
+        unsafe { &mut *(self.as_mut_ptr() as *mut MaybeUninit<T>) }
+    }
+
+    /// Convert into a unibox hosting the value, without copying it.
+    /// 
+    /// # Safety
+    /// 
+    /// The value must be fully initialized, see [`MaybeUninit::assume_init`].
+    pub unsafe fn assume_init(mut self) -> UniBox {
+        self.unibox.set_hosted(TypeMeta::of::<T>());
+        self.unibox
+    }
+}
@@ -20,8 +20,8 @@
 //! 
 //! UniBox offers two kinds of types:
 //! 
-//! - *Static*: uniboxes that store data without using the heap. They have a fixed size, and the type they host can't be bigger than that. Currently there are four types: [`UniBox32`], [`UniBox64`], [`UniBox128`] and [`UniBox256`], to store types up to 32, 64, 128 and 256 bytes. All these types are based on the generic static type, [`UniBoxN`], that can also be used to implement custom static uniboxes.
-//! - *Dynamic*: store data by allocating memory, like a regular Box. There is only one type, [`UniBox`].
+//! - *Static*: uniboxes that store data without using the heap. They have a fixed size, and the type they host can't be bigger than that. The capacity is a const generic of [`UniBoxSized`], and there are aliases for the most common ones: [`UniBox8`], [`UniBox16`], [`UniBox32`], [`UniBox64`], [`UniBox128`], [`UniBox256`], [`UniBox512`], [`UniBox1024`] and [`UniBox4096`], to store types up to 8, 16, 32, 64, 128, 256, 512, 1024 and 4096 bytes. Other sizes can be named with [`define_unibox!`], [`unibox_fits!`] checks at compile time that a type fits, and [`smallest_unibox!`] picks the smallest alias for a type. All these types are based on the generic static type, [`UniBoxN`], that can also be used to implement custom static uniboxes. Their storage is aligned to 16 bytes, enough for any primitive type, and [`UniBoxAligned`] allows picking a bigger alignment. To use a memory region provided by the application instead, like a `static` array, there is [`UniBoxRegion`], and [`UniBump`] hosts many values of different types in one.
+//! - *Dynamic*: store data by allocating memory, like a regular Box. The main type is [`UniBox`], [`AutoUniBox`] stores small values inline and only allocates for bigger ones, [`UniPool`] recycles the allocations of the uniboxes it creates, [`UniArena`] hosts many values of different types in a single buffer, [`UniSlotMap`] stores uniboxes behind generational keys, [`UniRc`] shares a hosted value among many owners, and [`UniCow`] clones a shared value on the first write.
 //! 
 //! ## Usage
 //! 
@@ -93,7 +93,7 @@
 //! 
 //! ## Uniboxing types with references
 //! 
-//! Is possible to unibox a type that contains a reference with non-static lifetime, like so:
+//! Is possible to unibox a type that contains a reference with non-static lifetime, using the unsafe constructor `new_unbounded`, like so:
 //! 
 //! ```
 //! # use unibox::{ Uniboxed, UniBox32 };
@@ -103,16 +103,34 @@
 //! 
 //! let arr = [1, 2, 3, 4, 5];
 //! 
-//! let ubox = UniBox32::new(
-//!     MyStruct {
-//!         my_ref: &arr
-//!     }
-//! ).expect("Failed uniboxing MyStruct");
+//! let ubox = unsafe {
+//!     UniBox32::new_unbounded(
+//!         MyStruct {
+//!             my_ref: &arr
+//!         }
+//!     )
+//! }.expect("Failed uniboxing MyStruct");
 //! 
 //! println!("{:#?}", unsafe { ubox.as_ref::<MyStruct>() }.my_ref);
 //! ```
 //! 
-//! But once the type is embedded inside a UniBox, the rust compiler looses track of it, and it won't be able to ensure that lifetime constraints are observed. For this reason, is the programmer who must make sure that no references are used after being droped the original value. That's the main reason why [`Uniboxed::as_ref`] and [`Uniboxed::as_mut_ref`] are unsafe.
+//! But once the type is embedded inside a UniBox, the rust compiler looses track of it, and it won't be able to ensure that lifetime constraints are observed. For this reason, is the programmer who must make sure that no references are used after being droped the original value, and that the unibox itself is dropped before, since the destructor of the hosted type may use them too. The safe constructors, like [`Uniboxed::new`], only accept `'static` types. That's the main reason why [`Uniboxed::as_ref`] and [`Uniboxed::as_mut_ref`] are unsafe.
+//! 
+//! When the hosted type has no references, or only `'static` ones, you can create the unibox with [`Uniboxed::new_any`] instead. It records the `TypeId` of the type, and allows accessing it safely with [`Uniboxed::downcast_ref`] and [`Uniboxed::downcast_mut`].
+//! 
+//! When the code using the values only needs a trait they share, declare the trait object with [`unibox_dyn!`] and create the uniboxes with `new_dyn`, like [`UniBoxN::new_dyn`]. [`Uniboxed::as_dyn`] and [`Uniboxed::as_dyn_mut`] give back the trait object without knowing the concrete type.
+//! 
+//! If a unibox will only host one type, wrap it in [`Typed`], that keeps the type in its signature and gives safe access through `Deref`.
+//! 
+//! To host different types with references safely, use [`UniBoxScoped`]. It carries the lifetime of the hosted value, so the compiler won't let it outlive the borrowed data.
+//! 
+//! ## Panic-free use
+//! 
+//! Firmware that can't link code with reachable panics can stick to the fallible subset of the API. Constructors return `Result`, and instead of the methods that panic when the requested type is not the hosted one, [`Uniboxed::try_as_ref`], [`Uniboxed::try_as_mut_ref`], [`Uniboxed::try_take`] and [`Uniboxed::try_into_inner`] return an error. Safe access goes through [`Uniboxed::downcast_ref`], [`Uniboxed::downcast_mut`] and [`Uniboxed::downcast`], that only check the `TypeId`, so they keep working after [`Uniboxed::set_id`]. Comparisons with [`Uniboxed::eq_dyn`] and [`Uniboxed::cmp_dyn`] only call the recorded hooks on values of the same `TypeId`, and [`CloneUniboxed::try_clone`] replaces `Clone::clone`. [`UniBoxN::new_const`] only panics in const context, where it's a compile error.
+//! 
+//! ## Threads
+//! 
+//! Since the hosted type is unknown, uniboxes are neither `Send` nor `Sync`. To move a unibox to another thread, create it wrapped in a [`SendUniBox`], that requires the hosted type to be `Send`. To share it between threads, use a [`SyncUniBox`], that requires `Send` and `Sync`. With the `alloc` feature, a [`UniArc`] also shares the ownership, and the `portable-atomic` feature makes it work on targets without atomic read-modify-write instructions, like `thumbv6m`.
 //! 
 //! ## Why not `Any`?
 //! 
@@ -134,6 +152,20 @@
 //! 
 //! If your environment doesn't provide the alloc crate, just disable the default features. If you do so, you won't be able to use [`UniBox`] type.
 //! 
+//! The minimum supported Rust version is 1.83.
+//! 
+//! The `poison` feature, disabled by default, zeroes the storage of a unibox right after the hosted value is dropped, both the static buffers and the heap allocations, so stale data doesn't linger in memory.
+//!
+//! The `debug-poison` feature overwrites the same storage with a `0xDD` pattern instead, only in debug builds, so a use-after-drop through a stale pointer reads obvious garbage during development. It takes precedence over `poison`.
+//! 
+//! The `zeroize` feature integrates with the [`zeroize`](https://docs.rs/zeroize) crate. It adds `new_zeroizing` constructors, for types implementing `Zeroize`, that wipe the hosted value before dropping it, and makes every unibox securely wipe its storage on drop. The `new_secret` constructors, for any type, make that wipe explicit, so code hosting keys or passwords doesn't build without the feature.
+//! 
+//! The `diagnostics` feature keeps a registry of live uniboxes, with their id, length and creation location, that can be printed with `dump_live`, e.g. from a panic handler.
+//! 
+//! The `stats` feature keeps global counters of live uniboxes, hosted bytes and their peak, and values per id, updated when uniboxes are created and dropped. `stats` and `stats_by_id` take a snapshot of them, to find leaks in long-running services.
+//! 
+//! The `bytemuck` feature adds `new_zeroed` constructors, for types implementing [`Zeroable`](https://docs.rs/bytemuck/latest/bytemuck/trait.Zeroable.html), that create a zeroed value directly inside the unibox, and `new_pod` constructors, for types implementing [`Pod`](https://docs.rs/bytemuck/latest/bytemuck/trait.Pod.html), whose uniboxes are cloned by copying the bytes. Pod uniboxes can also be exchanged between processes through shared memory: `Uniboxed::write_shared` copies the value after a `SharedHeader` with its layout and a fingerprint of its type, and `from_shared` constructors check them before rebuilding the unibox in the other process.
+//! 
 #![no_std]
 
 #[cfg(feature = "alloc")]
@@ -147,5 +179,47 @@ pub use stack::*;
 mod uniboxed;
 pub use uniboxed::*;
 
+mod typemeta;
+pub use typemeta::*;
+
+mod dynunibox;
+pub use dynunibox::*;
+
+mod typed;
+pub use typed::*;
+
+mod scoped;
+pub use scoped::*;
+
+mod handle;
+pub use handle::*;
+
+mod dyncast;
+pub use dyncast::*;
+
+mod cloneable;
+pub use cloneable::*;
+
+mod sendsync;
+pub use sendsync::*;
+
+mod error;
+pub use error::*;
+
+#[cfg(feature = "bytemuck")]
+mod shared;
+#[cfg(feature = "bytemuck")]
+pub use shared::SharedHeader;
+
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{dump_live, LIVE_CAPACITY};
+
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "stats")]
+pub use stats::{stats, stats_by_id, Stats, STATS_IDS};
+
 #[cfg(test)]
 mod tests;
@@ -48,7 +48,7 @@ fn _main() {
                     number: 101,
                     city: "Vila del Pingüí".to_owned(),
                     zip: 888888,
-                    country_code: ['A' as u8, 'D' as u8]
+                    country_code: [b'A', b'D']
                 }
             }
         ).expect("Couldn't create UniBox128 for User");
@@ -59,7 +59,7 @@ fn _main() {
                 number: 666,
                 city: "Infern".to_owned(),
                 zip: 55555,
-                country_code: ['C' as u8, 'T' as u8]
+                country_code: [b'C', b'T']
             }
         ).expect("Couldn't create UniBox64 for Address");
 
@@ -73,7 +73,7 @@ fn _main() {
                     number: 101,
                     city: "Vila del Pingüí".to_owned(),
                     zip: 888888,
-                    country_code: ['A' as u8, 'D' as u8]
+                    country_code: [b'A', b'D']
                 }
             }
         ).expect("Couldn't create dynamic UniBox for User");
@@ -102,7 +102,7 @@ fn main() {
                 number: 101,
                 city: "Vila del Pingüí".to_owned(),
                 zip: 888888,
-                country_code: ['A' as u8, 'D' as u8]
+                country_code: [b'A', b'D']
             }
         }
     ).expect("Couldn't create UniBox128 for User");
@@ -113,7 +113,7 @@ fn main() {
             number: 666,
             city: "Infern".to_owned(),
             zip: 55555,
-            country_code: ['C' as u8, 'T' as u8]
+            country_code: [b'C', b'T']
         }
     ).expect("Couldn't create UniBox128 for Address");
 
@@ -127,7 +127,7 @@ fn main() {
 
     println!("---- Vector of UniBoxes ----");
 
-    let v = vec!(ub1, ub2);
+    let v = [ub1, ub2];
 
     for b in v.iter() {
         println!("UniBox len = {}", b.len());
@@ -142,8 +142,8 @@ fn main() {
             street: "Carrer de l'Església".to_owned(),
             number: 203,
             city: "Calella".to_owned(),
-            zip: 08370,
-            country_code: ['C' as u8, 'T' as u8]
+            zip: 8370,
+            country_code: [b'C', b'T']
         }
     ).expect("Couldn't create UniBox64 for Address");
 
@@ -156,8 +156,8 @@ Address {
             street: "Carrer de l'Església".to_owned(),
             number: 203,
             city: "Calella".to_owned(),
-            zip: 08370,
-            country_code: ['C' as u8, 'T' as u8]
+            zip: 8370,
+            country_code: [b'C', b'T']
         }
     ).expect("Couldn't create dynamic UniBox for Address");
 
@@ -171,7 +171,7 @@ User {
                 number: 101,
                 city: "Vila del Pingüí".to_owned(),
                 zip: 888888,
-                country_code: ['A' as u8, 'D' as u8]
+                country_code: [b'A', b'D']
             }
         }
     ).expect("Couldn't create dynamic UniBox for User");
@@ -226,17 +226,21 @@ User {
     println!("---- Struct with lifetime ----");
 
     #[derive(Debug)]
+    #[allow(dead_code)]
     struct MyStruct<'a> {
-        color_ref: &'a Box<Color>
+        color_ref: &'a Color
     }
 
     let my_color = Box::new(Color::Red);
 
-    let ub10 = UniBox32::new(
-        MyStruct {
-            color_ref: &my_color
-        }
-    ).expect("Failed uniboxing MyStruct");
+    // MyStruct is not 'static, so it must be uniboxed with the unsafe constructor.
+    let ub10 = unsafe {
+        UniBox32::new_unbounded(
+            MyStruct {
+                color_ref: &my_color
+            }
+        )
+    }.expect("Failed uniboxing MyStruct");
 
     println!("{:#?}", unsafe { ub10.as_ref::<MyStruct>() });
     
@@ -0,0 +1,110 @@
+use core::marker::PhantomData;
+use crate::{DynUnibox, Uniboxed, UniBoxError};
+
+/// Unibox that carries the minimum lifetime of the hosted value, so it can host types with non-static references safely.
+///
+/// The compiler makes sure the unibox doesn't outlive `'a`, so dropping a borrowed value while it's still uniboxed is a compile error:
+///
+/// ```compile_fail
+/// use unibox::{ UniBoxScoped, UniBox32 };
+///
+/// let ubox = {
+///     let arr = [1, 2, 3, 4, 5];
+///     UniBoxScoped::<UniBox32>::new(&arr[..]).expect("Couldn't create UniBoxScoped")
+/// };
+/// ```
+///
+/// The unibox itself is not exposed, since cloning or moving it out would drop the lifetime again. Its properties can be accessed with [`UniBoxScoped::as_dyn`].
+pub struct UniBoxScoped<'a, U: Uniboxed> {
+    unibox: U,
+    _scope: PhantomData<&'a ()>
+}
+
+impl<'a, U: Uniboxed> UniBoxScoped<'a, U> {
+    /// Create a new UniBoxScoped instance.
+    ///
+    /// Returns Err if the type can't be hosted by `U`, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new<T: 'a>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            // Can't outlive 'a, and T lives at least that long
+            unibox: unsafe { U::new_unbounded(instance)? },
+            _scope: PhantomData
+        })
+    }
+
+    /// Create an empty UniBoxScoped instance, that hosts no value.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn empty() -> Self {
+        Self {
+            unibox: U::empty(),
+            _scope: PhantomData
+        }
+    }
+
+    /// Wrap a unibox.
+    ///
+    /// # Safety
+    ///
+    /// The hosted value must be valid for `'a`.
+    pub unsafe fn from_unibox(unibox: U) -> Self {
+        Self {
+            unibox,
+            _scope: PhantomData
+        }
+    }
+
+    /// Object-safe view of the unibox, to get its id, length, bytes, etc.
+    pub fn as_dyn(&self) -> &dyn DynUnibox {
+        &self.unibox
+    }
+
+    /// Mutable object-safe view of the unibox.
+    pub fn as_dyn_mut(&mut self) -> &mut dyn DynUnibox {
+        &mut self.unibox
+    }
+
+    /// Get reference to stored data using a type. See [`Uniboxed::as_ref`].
+    ///
+    /// # Safety
+    ///
+    /// Lifetimes are not part of the type id, so `T` must have the same lifetimes as the hosted value.
+    pub unsafe fn as_ref<T: 'a>(&self) -> &T {
+        self.unibox.as_ref()
+    }
+
+    /// Get mutable reference to stored data using a type. See [`Uniboxed::as_mut_ref`].
+    ///
+    /// # Safety
+    ///
+    /// Lifetimes are not part of the type id, so `T` must have the same lifetimes as the hosted value.
+    pub unsafe fn as_mut_ref<T: 'a>(&mut self) -> &mut T {
+        self.unibox.as_mut_ref()
+    }
+
+    /// Move the hosted value out, consuming the unibox. See [`Uniboxed::into_inner`].
+    ///
+    /// # Safety
+    ///
+    /// Lifetimes are not part of the type id, so `T` must have the same lifetimes as the hosted value.
+    pub unsafe fn into_inner<T: 'a>(self) -> T {
+        self.unibox.into_inner()
+    }
+
+    /// Convert into the wrapped unibox.
+    ///
+    /// # Safety
+    ///
+    /// The unibox must be dropped before `'a` ends, since the compiler won't track it anymore.
+    pub unsafe fn into_unibox(self) -> U {
+        self.unibox
+    }
+}
+
+/// Empty unibox, see [`UniBoxScoped::empty`].
+impl<U: Uniboxed> Default for UniBoxScoped<'_, U> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
@@ -0,0 +1,111 @@
+use core::ops::Deref;
+use crate::{Uniboxed, UniBoxError};
+
+/// Unibox hosting a `Send` value, that can be moved to another thread.
+/// 
+/// Uniboxes don't know the type they host, so they are neither `Send` nor `Sync`. This wrapper can only be created with values that are `Send`, and restores it.
+pub struct SendUniBox<U: Uniboxed> {
+    unibox: U
+}
+
+unsafe impl<U: Uniboxed> Send for SendUniBox<U> {}
+
+impl<U: Uniboxed> SendUniBox<U> {
+    /// Create a new SendUniBox instance.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_send<T: Send + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            unibox: U::new(instance)?
+        })
+    }
+
+    /// Wrap a unibox.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value must be `Send`.
+    pub const unsafe fn from_unibox(unibox: U) -> Self {
+        Self {
+            unibox
+        }
+    }
+
+    /// Mutable reference to the unibox.
+    /// 
+    /// # Safety
+    /// 
+    /// The unibox must keep hosting a `Send` value, e.g. it can't be swapped with another unibox.
+    pub unsafe fn as_unibox_mut(&mut self) -> &mut U {
+        &mut self.unibox
+    }
+
+    /// Convert into the wrapped unibox.
+    pub fn into_unibox(self) -> U {
+        self.unibox
+    }
+}
+
+impl<U: Uniboxed> Deref for SendUniBox<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.unibox
+    }
+}
+
+/// Unibox hosting a `Send` and `Sync` value, that can be moved to and shared with other threads.
+/// 
+/// Uniboxes don't know the type they host, so they are neither `Send` nor `Sync`. This wrapper can only be created with values that are `Send` and `Sync`, and restores both.
+pub struct SyncUniBox<U: Uniboxed> {
+    unibox: U
+}
+
+unsafe impl<U: Uniboxed> Send for SyncUniBox<U> {}
+unsafe impl<U: Uniboxed> Sync for SyncUniBox<U> {}
+
+impl<U: Uniboxed> SyncUniBox<U> {
+    /// Create a new SyncUniBox instance.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_sync<T: Send + Sync + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            unibox: U::new(instance)?
+        })
+    }
+
+    /// Wrap a unibox, e.g. one created with `new_const` to initialize a `static`.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value must be `Send` and `Sync`.
+    pub const unsafe fn from_unibox(unibox: U) -> Self {
+        Self {
+            unibox
+        }
+    }
+
+    /// Mutable reference to the unibox.
+    /// 
+    /// # Safety
+    /// 
+    /// The unibox must keep hosting a `Send` and `Sync` value, e.g. it can't be swapped with another unibox.
+    pub unsafe fn as_unibox_mut(&mut self) -> &mut U {
+        &mut self.unibox
+    }
+
+    /// Convert into the wrapped unibox.
+    pub fn into_unibox(self) -> U {
+        self.unibox
+    }
+}
+
+impl<U: Uniboxed> Deref for SyncUniBox<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.unibox
+    }
+}
@@ -0,0 +1,144 @@
+use core::{
+    alloc::Layout,
+    convert::TryInto,
+    mem
+};
+use bytemuck::Pod;
+use crate::{SharedError, TypeMeta};
+
+/// Header written before the payload of a unibox in a shared-memory segment, see [`Uniboxed::write_shared`](crate::Uniboxed::write_shared).
+///
+/// It records the layout of the hosted type, a fingerprint of its name, and the user metadata word, that can carry a schema version. All fields are in native byte order, since the segment is shared by processes of the same machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedHeader {
+    size: u32,
+    align: u32,
+    fingerprint: u64,
+    meta: u64
+}
+
+impl SharedHeader {
+    /// Length of the header in the segment. The payload starts right after it.
+    pub const LEN: usize = 32;
+    /// Version of the header format written by this crate.
+    pub const VERSION: u32 = 1;
+    const MAGIC: u32 = u32::from_ne_bytes(*b"UBOX");
+
+    /// Header of a hosted value described by *type_meta*.
+    fn new(type_meta: &TypeMeta, meta: usize) -> Self {
+        Self {
+            size: type_meta.size() as u32,
+            align: type_meta.align() as u32,
+            fingerprint: fingerprint(type_meta.name(), type_meta.layout()),
+            meta: meta as u64
+        }
+    }
+
+    /// Read and validate the header at the start of *segment*.
+    ///
+    /// Returns Err if the segment doesn't start with a header of this version, or is too short for the payload.
+    pub fn read(segment: &[u8]) -> Result<Self, SharedError> {
+        if segment.len() < Self::LEN {
+            return Err(SharedError::TooSmall {
+                size: Self::LEN,
+                available: segment.len()
+            });
+        }
+        let word = |offset: usize| u32::from_ne_bytes(segment[offset..offset + 4].try_into().unwrap_or_default());
+        let long = |offset: usize| u64::from_ne_bytes(segment[offset..offset + 8].try_into().unwrap_or_default());
+        if word(0) != Self::MAGIC {
+            return Err(SharedError::BadMagic);
+        }
+        if word(4) != Self::VERSION {
+            return Err(SharedError::Version(word(4)));
+        }
+        let header = Self {
+            size: word(8),
+            align: word(12),
+            fingerprint: long(16),
+            meta: long(24)
+        };
+        let size = Self::LEN + header.size as usize;
+        if segment.len() < size {
+            return Err(SharedError::TooSmall {
+                size,
+                available: segment.len()
+            });
+        }
+        Ok(header)
+    }
+
+    /// Write the header at the start of *segment*, that must be at least [`SharedHeader::LEN`] bytes long.
+    fn write(&self, segment: &mut [u8]) {
+        segment[0..4].copy_from_slice(&Self::MAGIC.to_ne_bytes());
+        segment[4..8].copy_from_slice(&Self::VERSION.to_ne_bytes());
+        segment[8..12].copy_from_slice(&self.size.to_ne_bytes());
+        segment[12..16].copy_from_slice(&self.align.to_ne_bytes());
+        segment[16..24].copy_from_slice(&self.fingerprint.to_ne_bytes());
+        segment[24..32].copy_from_slice(&self.meta.to_ne_bytes());
+    }
+
+    /// Check that the payload is a `T`, both in layout and type name.
+    pub fn check<T: Pod>(&self) -> Result<(), SharedError> {
+        let layout = Layout::new::<T>();
+        if self.layout() != Some(layout) {
+            return Err(SharedError::LayoutMismatch {
+                size: self.size as usize,
+                align: self.align as usize,
+                expected: layout
+            });
+        }
+        if self.fingerprint != fingerprint(core::any::type_name::<T>(), layout) {
+            return Err(SharedError::TypeMismatch(core::any::type_name::<T>()));
+        }
+        Ok(())
+    }
+
+    /// Size and alignment of the hosted type. None if they are not a valid layout.
+    pub fn layout(&self) -> Option<Layout> {
+        Layout::from_size_align(self.size as usize, self.align as usize).ok()
+    }
+
+    /// Fingerprint of the name and layout of the hosted type.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// User metadata word of the unibox, see [`Uniboxed::meta`](crate::Uniboxed::meta).
+    pub fn meta(&self) -> usize {
+        self.meta as usize
+    }
+}
+
+/// Copy the value at *hosted* to *segment*, after its header. See [`Uniboxed::write_shared`](crate::Uniboxed::write_shared).
+pub(crate) fn write_shared(type_meta: Option<&'static TypeMeta>, meta: usize, hosted: *const u8, segment: &mut [u8]) -> Result<usize, SharedError> {
+    let type_meta = type_meta.filter(|type_meta| type_meta.is_pod()).ok_or(SharedError::NotPod)?;
+    // Pod values have no uninitialized bytes
+    let bytes = unsafe { core::slice::from_raw_parts(hosted, type_meta.size()) };
+    let size = SharedHeader::LEN + bytes.len();
+    if segment.len() < size {
+        return Err(SharedError::TooSmall {
+            size,
+            available: segment.len()
+        });
+    }
+    SharedHeader::new(type_meta, meta).write(segment);
+    segment[SharedHeader::LEN..size].copy_from_slice(bytes);
+    Ok(size)
+}
+
+/// Read the `T` stored in *segment*, and the metadata word of its unibox.
+pub(crate) fn read_shared<T: Pod>(segment: &[u8]) -> Result<(T, usize), SharedError> {
+    let header = SharedHeader::read(segment)?;
+    header.check::<T>()?;
+    let payload = &segment[SharedHeader::LEN..SharedHeader::LEN + mem::size_of::<T>()];
+    Ok((bytemuck::pod_read_unaligned(payload), header.meta()))
+}
+
+/// FNV-1a hash of the type name and layout. Type names are only stable for the same compiler, so both sides must be built with the same toolchain.
+fn fingerprint(name: &str, layout: Layout) -> u64 {
+    name.bytes()
+        .chain((layout.size() as u64).to_le_bytes())
+        .chain((layout.align() as u64).to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
@@ -1,38 +1,62 @@
+use core::{
+    mem::MaybeUninit,
+    ptr
+};
+
 /// Interface for supported buffer types.
 /// 
-/// The internal buffer of all uniboxes must implement this trait.
-pub unsafe trait Buffer {
+/// The internal buffer of all uniboxes must implement this trait. Any type can be a backend of [`UniBoxN`](crate::UniBoxN), like an aligned wrapper or a struct placed in a linker section, as long as it's stored inline in the unibox. For memory owned by someone else, like a pool slot or a hardware buffer, use [`UniBoxRegion`](crate::UniBoxRegion).
+/// 
+/// Only [`Buffer::init`] is required, the other methods work on the bytes of the buffer itself.
+/// 
+/// ```
+/// use core::mem::MaybeUninit;
+/// use unibox::{ Buffer, UniBoxN };
+/// 
+/// #[repr(C, align(64))]
+/// struct CacheLine([MaybeUninit<u8>; 64]);
+/// 
+/// unsafe impl Buffer for CacheLine {
+///     fn init() -> Self {
+///         CacheLine([MaybeUninit::new(0); 64])
+///     }
+/// }
+/// 
+/// let ubox = UniBoxN::<CacheLine>::new([1u64; 8]).expect("Couldn't create UniBoxN");
+/// assert_eq!(UniBoxN::<CacheLine>::max_align(), 64);
+/// assert_eq!(ubox.as_ptr() as usize % 64, 0);
+/// ```
+/// 
+/// # Safety
+/// 
+/// Implementors must be plain byte storage: the first [`Buffer::CAPACITY`] bytes of the type, that can't be more than its size, are the storage, so [`Buffer::ptr`] has to point to the buffer itself, aligned to `align_of::<Self>()`, and the copy methods must write exactly to that memory. Those bytes must not be padding, so they are kept when the buffer is moved.
+/// 
+/// The storage must tolerate uninitialized bytes, like [`MaybeUninit<u8>`](MaybeUninit): hosted values are written with their own type, so their padding bytes are left uninitialized, and the buffer is moved by value with them. A plain `[u8; N]` can't be a buffer.
+pub unsafe trait Buffer: Sized {
+    /// Number of usable bytes.
+    const CAPACITY: usize = core::mem::size_of::<Self>();
     /// Init the type.
     fn init() -> Self;
     /// Raw pointer to type.
-    fn ptr<T>(&self) -> *const T;
-    /// Copy from byte array to type *len* bytes.
-    fn copy_from_byte(&mut self, src: &[u8], len: usize);
-    /// Copy from type to type *len* bytes.
-    fn copy_from_type(&mut self, src: &Self, len: usize);
-}
-
-unsafe impl Buffer for [u8; 32] {
-    fn init() -> Self {
-        [0; 32]
-    }
-
     fn ptr<T>(&self) -> *const T {
-        self.as_ptr() as *const T
+        self as *const Self as *const T
     }
-
+    /// Copy from byte array to type *len* bytes.
     fn copy_from_byte(&mut self, src: &[u8], len: usize) {
-        self[0..len].clone_from_slice(src);
+        let len = len.min(src.len()).min(Self::CAPACITY);
+        unsafe { ptr::copy_nonoverlapping(src.as_ptr(), self as *mut Self as *mut u8, len) };
     }
-
+    /// Copy from type to type *len* bytes.
     fn copy_from_type(&mut self, src: &Self, len: usize) {
-        self[0..len].clone_from_slice(&src[0..len]);
+        let len = len.min(Self::CAPACITY);
+        unsafe { ptr::copy_nonoverlapping(src as *const Self as *const u8, self as *mut Self as *mut u8, len) };
     }
 }
 
-unsafe impl Buffer for [u8; 64] {
+/// Buffer that is not zero-initialized, to save the memset when creating big uniboxes. Bytes not written by the hosted value stay uninitialized, so with the `poison` feature they are not poisoned either.
+unsafe impl<const N: usize> Buffer for [MaybeUninit<u8>; N] {
     fn init() -> Self {
-        [0; 64]
+        [MaybeUninit::uninit(); N]
     }
 
     fn ptr<T>(&self) -> *const T {
@@ -40,46 +64,77 @@ unsafe impl Buffer for [u8; 64] {
     }
 
     fn copy_from_byte(&mut self, src: &[u8], len: usize) {
-        self[0..len].clone_from_slice(src);
+        for (dst, src) in self[0..len].iter_mut().zip(src) {
+            *dst = MaybeUninit::new(*src);
+        }
     }
 
     fn copy_from_type(&mut self, src: &Self, len: usize) {
-        self[0..len].clone_from_slice(&src[0..len]);
+        self[0..len].copy_from_slice(&src[0..len]);
     }
 }
 
-unsafe impl Buffer for [u8; 128] {
-    fn init() -> Self {
-        [0; 128]
-    }
+/// Alignment of A bytes, for [`AlignedBytes`].
+pub struct Align<const A: usize>;
 
-    fn ptr<T>(&self) -> *const T {
-        self.as_ptr() as *const T
-    }
+/// Alignments supported by [`AlignedBytes`], the powers of two from 1 to 4096.
+pub trait SupportedAlign {
+    /// Zero-sized type with the alignment.
+    #[doc(hidden)]
+    type Marker;
+}
 
-    fn copy_from_byte(&mut self, src: &[u8], len: usize) {
-        self[0..len].clone_from_slice(src);
-    }
+mod marker {
+    macro_rules! align_markers {
+        ($($name:ident = $align:literal),*) => {
+            $(
+                #[repr(align($align))]
+                pub struct $name;
 
-    fn copy_from_type(&mut self, src: &Self, len: usize) {
-        self[0..len].clone_from_slice(&src[0..len]);
+                impl super::SupportedAlign for super::Align<$align> {
+                    type Marker = $name;
+                }
+            )*
+        };
     }
+
+    align_markers!(
+        A1 = 1, A2 = 2, A4 = 4, A8 = 8, A16 = 16, A32 = 32, A64 = 64,
+        A128 = 128, A256 = 256, A512 = 512, A1024 = 1024, A2048 = 2048, A4096 = 4096
+    );
 }
 
-unsafe impl Buffer for [u8; 256] {
+/// Byte buffer of N bytes aligned to A bytes, 16 by default, that is enough for any primitive type. Used by [`UniBoxAligned`](crate::UniBoxAligned).
+/// 
+/// Bytes are stored as [`MaybeUninit`], so hosting types with padding is sound: their uninitialized padding bytes are never read as `u8`.
+#[repr(C)]
+pub struct AlignedBytes<const N: usize, const A: usize = 16> where Align<A>: SupportedAlign {
+    _align: [<Align<A> as SupportedAlign>::Marker; 0],
+    bytes: [MaybeUninit<u8>; N]
+}
+
+unsafe impl<const N: usize, const A: usize> Buffer for AlignedBytes<N, A> where Align<A>: SupportedAlign {
+    // Bytes after N are padding when N is not a multiple of the alignment
+    const CAPACITY: usize = N;
+
     fn init() -> Self {
-        [0; 256]
+        Self {
+            _align: [],
+            bytes: [MaybeUninit::new(0); N]
+        }
     }
 
     fn ptr<T>(&self) -> *const T {
-        self.as_ptr() as *const T
+        self.bytes.as_ptr() as *const T
     }
 
     fn copy_from_byte(&mut self, src: &[u8], len: usize) {
-        self[0..len].clone_from_slice(src);
+        for (dst, src) in self.bytes[0..len].iter_mut().zip(src) {
+            *dst = MaybeUninit::new(*src);
+        }
     }
 
     fn copy_from_type(&mut self, src: &Self, len: usize) {
-        self[0..len].clone_from_slice(&src[0..len]);
+        self.bytes[0..len].copy_from_slice(&src.bytes[0..len]);
     }
 }
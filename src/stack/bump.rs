@@ -0,0 +1,175 @@
+use core::{
+    any::TypeId,
+    marker::PhantomData,
+    mem,
+    ops::Drop,
+    ptr,
+    slice
+};
+use crate::{ArenaHandle, TypeMeta, TypeMetaOf};
+
+/// Value hosted in a bump arena, stored at the end of the region.
+struct Entry {
+    offset: usize,
+    type_meta: &'static TypeMeta
+}
+
+/// Arena that hosts many values of different types in a memory region provided by the application, referenced by [`ArenaHandle`]. The `no_std` counterpart of [`UniArena`](crate::UniArena).
+/// 
+/// Values are placed from the start of the region, and their bookkeeping from the end, so the region is the only memory used. They can't be removed one by one: [`UniBump::reset`], or dropping the arena, drops all of them.
+/// 
+/// ```
+/// use unibox::UniBump;
+/// 
+/// static mut REGION: [u8; 256] = [0; 256];
+/// 
+/// let mut bump = UniBump::new(unsafe { &mut *core::ptr::addr_of_mut!(REGION) });
+/// let num = bump.alloc_value(10u64).expect("Region is full");
+/// assert_eq!(bump.get(num), Some(&10));
+/// bump.reset();
+/// assert_eq!(bump.get(num), None);
+/// ```
+pub struct UniBump {
+    ptr: *mut u8,
+    capacity: usize,
+    top: usize,
+    len: usize,
+    epoch: u32,
+    _region: PhantomData<&'static mut [u8]>
+}
+
+impl UniBump {
+    /// Create an empty arena over *region*.
+    pub fn new(region: &'static mut [u8]) -> Self {
+        Self {
+            ptr: region.as_mut_ptr(),
+            capacity: region.len(),
+            top: 0,
+            len: 0,
+            epoch: 0,
+            _region: PhantomData
+        }
+    }
+
+    /// Drop all the hosted values and give the region back, with the used bytes zeroed.
+    pub fn into_region(mut self) -> &'static mut [u8] {
+        self.reset();
+        // The arena is empty, so it won't touch the region anymore
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.capacity) }
+    }
+
+    /// Host a value in the arena, and return its handle.
+    /// 
+    /// Returns Err with the value if there is no room left in the region.
+    pub fn alloc_value<T: 'static>(&mut self, instance: T) -> Result<ArenaHandle<T>, T> {
+        let start = self.ptr as usize;
+        let offset = match (start + self.top).checked_add(mem::align_of::<T>() - 1) {
+            Some(addr) => (addr & !(mem::align_of::<T>() - 1)) - start,
+            None => return Err(instance)
+        };
+        let end = offset.saturating_add(mem::size_of::<T>());
+        let entry = match self.entry_offset(self.len) {
+            Some(entry) if end <= entry => entry,
+            _ => return Err(instance)
+        };
+        unsafe {
+            ptr::write(self.ptr.add(offset) as *mut T, instance);
+            ptr::write(self.ptr.add(entry) as *mut Entry, Entry {
+                offset,
+                type_meta: &TypeMetaOf::<T>::ANY
+            });
+        }
+        self.top = end;
+        self.len += 1;
+        Ok(ArenaHandle::new(self.len - 1, self.epoch))
+    }
+
+    /// Get reference to the value of *handle*. None if the handle is from before a reset.
+    pub fn get<T: 'static>(&self, handle: ArenaHandle<T>) -> Option<&T> {
+        let offset = self.offset_of(handle)?;
+        Some(unsafe { &*(self.ptr.add(offset) as *const T) })
+    }
+
+    /// Get mutable reference to the value of *handle*. None if the handle is from before a reset.
+    pub fn get_mut<T: 'static>(&mut self, handle: ArenaHandle<T>) -> Option<&mut T> {
+        let offset = self.offset_of(handle)?;
+        Some(unsafe { &mut *(self.ptr.add(offset) as *mut T) })
+    }
+
+    /// Number of hosted values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The arena hosts no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes of the region used by the hosted values and their bookkeeping.
+    pub fn used(&self) -> usize {
+        self.top + self.len * mem::size_of::<Entry>()
+    }
+
+    /// Length of the region.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Drop all the hosted values, in reverse order. Handles created before are no longer valid.
+    /// 
+    /// The bytes used by the values and their bookkeeping are zeroed, since padding bytes of the values may be uninitialized.
+    pub fn reset(&mut self) {
+        let entries = self.len * mem::size_of::<Entry>();
+        while self.len > 0 {
+            self.len -= 1;
+            let entry = unsafe { &*self.entry(self.len) };
+            if let Some(drop_fn) = entry.type_meta.drop_fn {
+                drop_fn(unsafe { self.ptr.add(entry.offset) });
+            }
+        }
+        unsafe {
+            ptr::write_bytes(self.ptr, 0, self.top);
+            // Entries end right after the first one
+            if let Some(first) = self.entry_offset(0).filter(|_| entries > 0) {
+                ptr::write_bytes(self.ptr.add(first + mem::size_of::<Entry>() - entries), 0, entries);
+            }
+        }
+        self.top = 0;
+        self.epoch = self.epoch.wrapping_add(1);
+    }
+
+    /// Offset in the region of the entry at *index*, if it fits. Entries are stacked down from the end of the region.
+    fn entry_offset(&self, index: usize) -> Option<usize> {
+        let start = self.ptr as usize;
+        let end = (start + self.capacity) & !(mem::align_of::<Entry>() - 1);
+        let size = index.checked_add(1)?.checked_mul(mem::size_of::<Entry>())?;
+        end.checked_sub(size)?.checked_sub(start)
+    }
+
+    /// Pointer to the entry at *index*, that must be lower than the number of values.
+    fn entry(&self, index: usize) -> *const Entry {
+        // Hosted entries always fit
+        unsafe { self.ptr.add(self.entry_offset(index).unwrap_or_default()) as *const Entry }
+    }
+
+    /// Offset of the value of *handle*, if it's still valid and hosts a `T`.
+    fn offset_of<T: 'static>(&self, handle: ArenaHandle<T>) -> Option<usize> {
+        if handle.epoch != self.epoch || handle.index >= self.len {
+            return None;
+        }
+        let entry = unsafe { &*self.entry(handle.index) };
+        if entry.type_meta.type_id() == Some(TypeId::of::<T>()) {
+            Some(entry.offset)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl Drop for UniBump {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
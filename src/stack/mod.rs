@@ -6,3 +6,9 @@ pub use narybox::*;
 
 mod staticbox;
 pub use staticbox::*;
+
+mod region;
+pub use region::*;
+
+mod bump;
+pub use bump::*;
@@ -1,26 +1,97 @@
 use core::{
+    alloc::Layout,
+    any::TypeId,
+    cmp::Ordering,
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    marker::PhantomData,
     slice,
-    mem,
-    ptr,
-    ops::Drop
+    mem::{
+        self,
+        ManuallyDrop,
+        MaybeUninit
+    },
+    ptr::{
+        self,
+        NonNull
+    },
+    ops::Drop,
+    pin::Pin
 };
 use super::Buffer;
+use crate::uniboxed::{ct_eq_bytes, Hosted};
+use crate::{AccessError, DynCast, OnDropHook, TypeMeta, TypeMetaOf, UniBoxError};
+use crate::typemeta::TypeMetaDyn;
+#[cfg(feature = "bytemuck")]
+use crate::SharedError;
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::LiveToken;
+#[cfg(feature = "stats")]
+use crate::stats::StatsToken;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "bytemuck")]
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 /// Generic static unibox that can implement any [`Buffer`].
 /// 
 /// This is the base of other static types, and should not be used directly. Use it only to implement your custom static unibox type.
+/// 
+/// Since the hosted type is unknown, uniboxes are neither `Send` nor `Sync`. See [`SendUniBox`](crate::SendUniBox) and [`SyncUniBox`](crate::SyncUniBox).
+/// 
+/// # Layout
+/// 
+/// The struct is `repr(C)`, with this field order, so it can be placed in shared memory or passed through FFI when both sides agree on it:
+/// 
+/// 1. The buffer `B`, at offset 0, so [`UniBoxN::as_ptr`] of an inline value is the address of the unibox.
+/// 2. Pointer to the [`TypeMeta`] of the hosted type, null if empty.
+/// 3. Pointer to the bytes of the id, never null.
+/// 4. Length of the id, `u32`.
+/// 5. Length of the hosted value, `u32`.
+/// 6. User metadata word, `usize`.
+/// 7. On-drop hook, a function pointer, null if none.
+/// 8. With the `alloc` feature, a `bool` flag set when the value is boxed, see `new_or_boxed`.
+/// 9. With the `diagnostics` feature, a private token.
+/// 10. With the `stats` feature, another private token.
+/// 
+/// The layout only changes with the `alloc`, `diagnostics` and `stats` features, that add fields at the end. Pointers are only valid in the address space that created them, and the hooks of a [`TypeMeta`] in the binary that generated it.
+#[repr(C)]
 pub struct UniBoxN<B: Buffer> {
     data: B,
-    len: usize,
-    autodrop: fn(&Self),
-    id: &'static str
+    // Size, alignment and hooks are shared by all the uniboxes hosting the same type
+    type_meta: Option<&'static TypeMeta>,
+    // The id is split in pointer and length, so its length and the one of the value share a word. Never null, so `Option<UniBoxN>` is as big as a UniBoxN
+    id_ptr: NonNull<u8>,
+    id_len: u32,
+    len: u32,
+    meta: usize,
+    on_drop: Option<OnDropHook>,
+    _local: PhantomData<*const ()>,
+    // The buffer holds a pointer to the hosted value, see `new_or_boxed`
+    #[cfg(feature = "alloc")]
+    boxed: bool,
+    #[cfg(feature = "diagnostics")]
+    live: LiveToken,
+    #[cfg(feature = "stats")]
+    stats: StatsToken
 }
 
 impl<B: Buffer> UniBoxN<B> {
     /// Create a new UniBox instance.
     /// 
+    /// The hosted type must be `'static`, see [`UniBoxN::new_unbounded`] for other types.
+    /// 
     /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
-    pub fn new<T: Sized>(instance: T) -> Result<Self, ()> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
         Self::new_with_id(instance, core::any::type_name::<T>())
     }
 
@@ -29,69 +100,951 @@ impl<B: Buffer> UniBoxN<B> {
     /// Accepts an *instance* and an *id*: a custom defined identifier used to know what type lies inside.
     /// 
     /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
-    pub fn new_with_id<T: Sized>(instance: T, id: &'static str) -> Result<Self, ()> {
-        let bytes = unsafe {
-            slice::from_raw_parts(
-                (&instance as *const T) as *const u8,
-                mem::size_of::<T>()
-            )
-        };
-        let autodrop = |_self: &Self| {
-            mem::drop(unsafe { _self.as_owned::<T>() });
-        };
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_with_id<T: 'static>(instance: T, id: &'static str) -> Result<Self, UniBoxError> {
+        unsafe { Self::new_unbounded_with_id(instance, id) }
+    }
+
+    /// Create a new UniBox instance hosting a type that may contain non-static references.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    /// 
+    /// # Safety
+    /// 
+    /// See [`Uniboxed::new_unbounded`](crate::Uniboxed::new_unbounded).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn new_unbounded<T: Sized>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_unbounded_with_id(instance, core::any::type_name::<T>())
+    }
+
+    /// Create a new UniBox instance hosting a type that may contain non-static references, with a custom *id*.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    /// 
+    /// # Safety
+    /// 
+    /// See [`Uniboxed::new_unbounded`](crate::Uniboxed::new_unbounded).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn new_unbounded_with_id<T: Sized>(instance: T, id: &'static str) -> Result<Self, UniBoxError> {
+        Self::fits::<T>()?;
+        let mut unibox = Self::empty();
+        unibox.write(instance, id);
+        Ok(unibox)
+    }
+
+    /// Create a new UniBox instance whose hosted value is never dropped, like [`ManuallyDrop`].
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_manually_drop<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_id(ManuallyDrop::new(instance), core::any::type_name::<T>())
+    }
+
+    /// Create a new UniBox instance without checking the size of the type, for hot paths.
+    /// 
+    /// # Safety
+    /// 
+    /// The struct must not be bigger than N bytes (N being the size of the unibox), nor have a bigger alignment than the buffer, e.g. verified with a const assertion. Only checked in debug builds.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn new_unchecked<T: Sized>(instance: T) -> Self {
+        debug_assert!(Self::fits::<T>().is_ok(), "Type doesn't fit in the unibox");
+        let mut unibox = Self::empty();
+        ptr::write_unaligned(unibox.as_mut_ptr() as *mut T, instance);
+        unibox.set_hosted(core::any::type_name::<T>(), TypeMeta::of::<T>());
+        unibox
+    }
+
+    /// Create a new UniBox instance, constructing the value directly inside the buffer.
+    /// 
+    /// Closure *f* gets the uninitialized memory of the value, so big types can be built without an extra copy on the stack.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    /// 
+    /// # Safety
+    /// 
+    /// *f* must fully initialize the value.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn new_with<T: Sized>(f: impl FnOnce(&mut MaybeUninit<T>)) -> Result<Self, UniBoxError> {
+        Self::fits::<T>()?;
+        let mut unibox = Self::empty();
+        f(&mut *(unibox.as_mut_ptr() as *mut MaybeUninit<T>));
+        unibox.set_hosted(core::any::type_name::<T>(), TypeMeta::of::<T>());
+        Ok(unibox)
+    }
+
+    /// Create a new UniBox instance hosting a zeroed `T`, without copying any value.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroed<T: Zeroable + 'static>() -> Result<Self, UniBoxError> {
+        unsafe {
+            Self::new_with(|slot: &mut MaybeUninit<T>| {
+                ptr::write_bytes(slot.as_mut_ptr() as *mut u8, 0, mem::size_of::<T>());
+            })
+        }
+    }
+
+    /// Create a new UniBox instance hosting a value that can be accessed as the trait object of `C`, with [`UniBoxN::as_dyn`]. `C` is declared with [`unibox_dyn!`](crate::unibox_dyn).
+    /// 
+    /// The value can also be accessed safely with [`UniBoxN::downcast_ref`].
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_dyn<C: DynCast<T>, T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaDyn::<T, C>::DYN)
+    }
+
+    /// Create a new UniBox instance hosting a [`Pod`] value, that is cloned by copying its bytes, without a clone hook.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_pod<T: Pod>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::POD)
+    }
+
+    /// Rebuild a UniBox instance hosting the Pod `T` written by another process to a shared-memory segment, see [`UniBoxN::write_shared`]. The user metadata word is restored too.
+    /// 
+    /// Returns Err if the segment doesn't hold a `T`, checked with [`SharedHeader::check`](crate::SharedHeader::check), or it doesn't fit in N bytes.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_shared<T: Pod>(segment: &[u8]) -> Result<Self, SharedError> {
+        let (instance, meta) = crate::shared::read_shared::<T>(segment)?;
+        let mut unibox = Self::new_pod(instance)?;
+        unibox.set_meta(meta);
+        Ok(unibox)
+    }
+
+    /// Rebuild a UniBox instance from the raw bytes of a value, e.g. received over a serial link.
+    /// 
+    /// The hosted value will never be dropped, and its alignment is unknown, see [`UniBoxN::layout`]. Its [`TypeMeta`] has no name and a size of 0.
+    /// 
+    /// Returns Err if the bytes are more than N (N being the size of the unibox).
+    /// 
+    /// # Safety
+    /// 
+    /// Only valid for Pod-like types, that don't implement Drop and don't contain pointers. *bytes* must be a valid image of the type identified by *id*.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn from_bytes_with_id(bytes: &[u8], id: &'static str) -> Result<Self, UniBoxError> {
         let len = bytes.len();
-        if len > mem::size_of::<B>() {
-            Err(())
+        if len > Self::capacity() {
+            return Err(UniBoxError::TooBig {
+                size: len,
+                capacity: Self::capacity()
+            });
+        }
+        let mut unibox = Self::empty();
+        unibox.data.copy_from_byte(bytes, len);
+        unibox.set_hosted_raw(id, len, &TypeMeta::RAW);
+        Ok(unibox)
+    }
+
+    /// Create a new UniBox instance in const context, e.g. to initialize a `static`.
+    /// 
+    /// Since [`core::any::type_name`] is not const, the *id* must be provided. Use the type name of `T` (e.g. `"u32"`) if you plan to access the value with [`UniBoxN::as_ref`].
+    /// 
+    /// To be used in a `static`, wrap it in a [`SyncUniBox`](crate::SyncUniBox). With the `diagnostics` feature, uniboxes created this way are not registered as live.
+    /// 
+    /// Panics if the struct is bigger than N bytes (N being the size of the unibox) or more aligned than the buffer, that in const context is a compile error. `T` must not have padding bytes, or const evaluation will fail too.
+    pub const fn new_const<T: Copy>(instance: T, id: &'static str) -> Self {
+        assert!(mem::size_of::<T>() <= Self::capacity(), "Type is bigger than the unibox");
+        assert!(mem::align_of::<T>() <= mem::align_of::<B>(), "Type is more aligned than the unibox");
+        let mut data = MaybeUninit::<B>::zeroed();
+        let data = unsafe {
+            ptr::write_unaligned(data.as_mut_ptr() as *mut T, instance);
+            data.assume_init()
+        };
+        Self {
+            data,
+            // Copy types have no drop glue
+            type_meta: Some(TypeMeta::of::<T>()),
+            id_ptr: unsafe { NonNull::new_unchecked(id.as_ptr() as *mut u8) },
+            id_len: id.len() as u32,
+            len: mem::size_of::<T>() as u32,
+            meta: 0,
+            on_drop: None,
+            _local: PhantomData,
+            #[cfg(feature = "alloc")]
+            boxed: false,
+            #[cfg(feature = "diagnostics")]
+            live: LiveToken::new_const(),
+            #[cfg(feature = "stats")]
+            stats: StatsToken::new_const()
+        }
+    }
+
+    /// Create a new UniBox instance, or if the value doesn't fit, move it to the heap and host the [`Box`](alloc::boxed::Box) instead.
+    /// 
+    /// Keeps collections of static uniboxes uniform while tolerating occasional oversized values. The indirection is recorded, so [`UniBoxN::as_ref`] and friends still give a `&T`, and the id and length are those of `T`. See [`UniBoxN::is_boxed`].
+    /// 
+    /// Returns Err if not even the pointer fits in the unibox, or if the allocation fails. See [`set_alloc_error_hook`](crate::set_alloc_error_hook) for allocation failures.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_or_boxed<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        if Self::fits::<T>().is_ok() {
+            return Self::new(instance);
+        }
+        if mem::size_of::<*mut T>() > Self::capacity() {
+            return Err(UniBoxError::TooBig {
+                size: mem::size_of::<T>(),
+                capacity: Self::capacity()
+            });
+        }
+        // Allocated like a Box, so it can be turned into one, but failures are reported instead of aborting
+        let layout = Layout::new::<T>();
+        let boxed = if layout.size() == 0 {
+            NonNull::<T>::dangling().as_ptr()
         }
         else {
-            let mut data = B::init();
-            data.copy_from_byte(bytes, len);
-            mem::forget(instance);
-            Ok(
-                Self {
-                    data,
-                    len,
-                    autodrop,
-                    id
-                }
-            )
+            crate::heap::alloc(layout) as *mut T
+        };
+        if boxed.is_null() {
+            return Err(UniBoxError::AllocFailed(layout));
+        }
+        let mut unibox = Self::empty();
+        // The buffer may be less aligned than a pointer
+        unsafe {
+            ptr::write(boxed, instance);
+            ptr::write_unaligned(unibox.as_mut_ptr_of::<*mut T>(), boxed);
         }
+        unibox.boxed = true;
+        unibox.set_hosted(core::any::type_name::<T>(), TypeMeta::of::<T>());
+        Ok(unibox)
+    }
+
+    /// The hosted value was moved to the heap by [`UniBoxN::new_or_boxed`].
+    #[cfg(feature = "alloc")]
+    pub fn is_boxed(&self) -> bool {
+        self.boxed
+    }
+
+    /// Create an empty UniBox instance, that hosts no value.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn empty() -> Self {
+        Self {
+            data: B::init(),
+            type_meta: None,
+            id_ptr: NonNull::dangling(),
+            id_len: 0,
+            len: 0,
+            meta: 0,
+            on_drop: None,
+            _local: PhantomData,
+            #[cfg(feature = "alloc")]
+            boxed: false,
+            #[cfg(feature = "diagnostics")]
+            live: LiveToken::new("", 0),
+            #[cfg(feature = "stats")]
+            stats: StatsToken::new()
+        }
+    }
+
+    /// Create a new UniBox instance that records the [`TypeId`] of the hosted type, enabling [`UniBoxN::downcast_ref`] and [`UniBoxN::downcast_mut`].
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::ANY)
+    }
+
+    /// Create a new UniBox instance that wipes the hosted value on drop.
+    /// 
+    /// Before the hosted destructor runs, [`Zeroize::zeroize`] is called on the value, so secrets owned by it (even on the heap) are wiped too.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroizing<T: Zeroize + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::ZEROIZE)
+    }
+
+    /// Create a new UniBox instance hosting secret material, like keys or passwords, even if it doesn't implement [`Zeroize`].
+    /// 
+    /// The buffer, or the allocation of a boxed value, is wiped with volatile writes after the hosted value is dropped. The `zeroize` feature does this for every unibox, but this constructor only exists with the feature, so the wipe can't be disabled by accident.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_secret<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, TypeMeta::of::<T>())
+    }
+
+    /// Create a new UniBox instance that can be compared with [`UniBoxN::eq_dyn`].
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_eq<T: PartialEq + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::EQ)
+    }
+
+    /// Create a new UniBox instance that can be hashed and compared, to be used as a key in hash maps.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_hashable<T: Hash + Eq + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::HASH)
+    }
+
+    /// Create a new UniBox instance that can be ordered with [`UniBoxN::cmp_dyn`] and compared with [`UniBoxN::eq_dyn`].
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_ord<T: Ord + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::ORD)
+    }
+
+    /// Create a new UniBox instance whose [`Debug`] implementation prints the hosted value.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_debuggable<T: Debug + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::DEBUG)
+    }
+
+    /// Create a new UniBox instance that can be cloned, cloning the hosted value.
+    /// 
+    /// Returns Err if the struct is bigger than N bytes (N being the size of the unibox).
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_cloneable<T: Clone + 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Self::new_with_meta(instance, &TypeMetaOf::<T>::CLONE)
+    }
+
+    /// The unibox can be cloned: it's empty or was created with [`UniBoxN::new_cloneable`] or `new_pod`.
+    pub fn is_cloneable(&self) -> bool {
+        self.type_meta.is_none_or(TypeMeta::is_cloneable)
+    }
+
+    /// Clone the unibox, with its id, metadata and hooks.
+    /// 
+    /// Returns None if the unibox is not cloneable.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn try_clone(&self) -> Option<Self> {
+        let mut unibox = Self::empty();
+        if let Some(type_meta) = self.type_meta.filter(|type_meta| type_meta.is_cloneable()) {
+            match type_meta.clone {
+                Some(clone) => clone(self.as_ptr(), unibox.as_mut_ptr()),
+                // Pod values are copied bytewise
+                None => unsafe { ptr::copy_nonoverlapping(self.as_ptr(), unibox.as_mut_ptr(), self.len()) }
+            }
+            unibox.set_hosted_raw(self.id(), self.len(), type_meta);
+            unibox.meta = self.meta;
+        }
+        else if !self.is_empty() {
+            return None;
+        }
+        unibox.on_drop = self.on_drop;
+        Some(unibox)
     }
 
     /// Get reference to stored data using a type.
     /// 
     /// **WARNING**: If you try to cast a type other than the one actually hosted, you may get a panic or any undefined behavior.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
     pub unsafe fn as_ref<T: Sized>(&self) -> &T {
         self.integrity_checks::<T>();
-        mem::transmute::<&B, &T>(&self.data)
+        &*self.as_ptr_of::<T>()
     }
 
     /// Get mutable reference to stored data using a type.
     /// 
     /// **WARNING**: If you try to cast a type other than the one actually hosted, you may get a panic or any undefined behavior.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
     pub unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
         self.integrity_checks::<T>();
-        mem::transmute::<&mut B, &mut T>(&mut self.data)
+        &mut *self.as_mut_ptr_of::<T>()
+    }
+
+    /// Get pinned reference to stored data using a type.
+    /// 
+    /// The unibox is [`Unpin`], so pinning it doesn't stop it from being moved. The hosted value is pinned only by the promise of the caller.
+    /// 
+    /// # Safety
+    /// 
+    /// See [`UniBoxN::as_ref`]. Unless the hosted type is [`Unpin`], the unibox must not be moved after pinning, and the hosted value must not be taken or replaced, until it's dropped.
+    pub unsafe fn as_pin<T: Sized>(self: Pin<&Self>) -> Pin<&T> {
+        self.map_unchecked(|unibox| unibox.as_ref::<T>())
+    }
+
+    /// Get pinned mutable reference to stored data using a type, e.g. to poll a hosted future.
+    /// 
+    /// # Safety
+    /// 
+    /// See [`UniBoxN::as_mut_ref`]. Unless the hosted type is [`Unpin`], the unibox must not be moved after pinning, and the hosted value must not be taken or replaced, until it's dropped, see [`UniBoxN::as_pin`].
+    pub unsafe fn as_pin_mut<T: Sized>(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.map_unchecked_mut(|unibox| unibox.as_mut_ref::<T>())
+    }
+
+    /// Get reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_as_ref<T: Sized>(&self) -> Result<&T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&*self.as_ptr_of::<T>())
+    }
+
+    /// Get mutable reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_as_mut_ref<T: Sized>(&mut self) -> Result<&mut T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&mut *self.as_mut_ptr_of::<T>())
+    }
+
+    /// Move stored data out of the unibox, consuming it.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, you may get a panic or any undefined behavior.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn into_inner<T: Sized>(mut self) -> T {
+        self.take()
+    }
+
+    /// Consume the unibox without running the destructor of the hosted value, e.g. when its resources were already handed off elsewhere.
+    pub fn forget_inner(mut self) {
+        // A boxed value also owns its allocation
+        #[cfg(feature = "alloc")]
+        if let Some(type_meta) = self.type_meta.filter(|type_meta| self.boxed && type_meta.size != 0) {
+            unsafe { self.free_boxed(type_meta.layout()) };
+        }
+        self.clear();
+    }
+
+    /// Move stored data out of the unibox, leaving it empty.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, you may get a panic or any undefined behavior.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn take<T: Sized>(&mut self) -> T {
+        self.integrity_checks::<T>();
+        let instance = self.as_owned::<T>();
+        self.clear();
+        instance
+    }
+
+    /// Move stored data out of the unibox, leaving it empty, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type. The hosted value is kept in that case.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_take<T: Sized>(&mut self) -> Result<T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        let instance = self.as_owned::<T>();
+        self.clear();
+        Ok(instance)
+    }
+
+    /// Move stored data out of the unibox, consuming it, without panicking.
+    /// 
+    /// Returns Err with the unibox if it is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn try_into_inner<T: Sized>(mut self) -> Result<T, Self> {
+        match self.try_take() {
+            Ok(instance) => Ok(instance),
+            Err(_) => Err(self)
+        }
+    }
+
+    /// Put a new value in the unibox.
+    /// 
+    /// If the hosted type is the same, the value is swapped and the old one returned. Otherwise the hosted value, if any, is dropped, and the new one gets an id assigned with [`core::any::type_name`].
+    /// 
+    /// Returns Err with the new value if it is bigger than N bytes (N being the size of the unibox). The hosted value is kept in that case.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    pub unsafe fn replace<T: Sized>(&mut self, instance: T) -> Result<Option<T>, T> {
+        if !self.is_empty() && self.check_type::<T>() {
+            Ok(Some(mem::replace(self.as_mut_ref::<T>(), instance)))
+        }
+        else if Self::fits::<T>().is_err() {
+            Err(instance)
+        }
+        else {
+            self.drop_hosted();
+            self.store(instance, core::any::type_name::<T>()).map(|_| None)
+        }
+    }
+
+    /// Move the hosted value, with its id, metadata and hooks, to a unibox with a different buffer, usually a bigger one.
+    /// 
+    /// The raw bytes are moved, no constructor nor destructor is run, so batches of uniboxes can be re-homed into a collection of a uniform size.
+    /// 
+    /// Returns Err with the unibox if the hosted value doesn't fit in the new buffer.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    #[allow(clippy::result_large_err)]
+    pub fn upsize<C: Buffer>(mut self) -> Result<UniBoxN<C>, Self> {
+        let (size, align) = (self.len(), self.layout().align());
+        // A boxed value is moved as an unaligned pointer
+        #[cfg(feature = "alloc")]
+        let (size, align) = if self.boxed { (mem::size_of::<*mut u8>(), 1) } else { (size, align) };
+        if size > UniBoxN::<C>::capacity() || align > mem::align_of::<C>() {
+            return Err(self);
+        }
+        let mut unibox = UniBoxN::<C>::empty();
+        unsafe {
+            ptr::copy_nonoverlapping(self.data.ptr::<u8>(), &mut unibox.data as *mut C as *mut u8, size);
+        }
+        unibox.set_hosted_from(self.hosted());
+        #[cfg(feature = "alloc")]
+        {
+            unibox.boxed = self.boxed;
+        }
+        // The value was moved out
+        self.clear();
+        Ok(unibox)
+    }
+
+    /// Exchange the hosted values, with their ids, metadata and hooks, between two uniboxes. No value is dropped.
+    pub fn swap(&mut self, other: &mut Self) {
+        mem::swap(self, other);
+    }
+
+    /// The unibox hosts no value.
+    pub fn is_empty(&self) -> bool {
+        self.type_meta.is_none()
+    }
+
+    /// Raw pointer to stored data.
+    pub fn as_ptr(&self) -> *const u8 {
+        #[cfg(feature = "alloc")]
+        if self.boxed {
+            return unsafe { ptr::read_unaligned(self.data.ptr::<*const u8>()) };
+        }
+        self.data.ptr()
+    }
+
+    /// Raw mutable pointer to stored data.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        #[cfg(feature = "alloc")]
+        if self.boxed {
+            return unsafe { ptr::read_unaligned(self.data.ptr::<*mut u8>()) };
+        }
+        &mut self.data as *mut B as *mut u8
+    }
+
+    /// Raw pointer to stored data, cast to `T`. No type checks are done.
+    pub fn as_ptr_of<T: Sized>(&self) -> *const T {
+        self.as_ptr() as *const T
+    }
+
+    /// Raw mutable pointer to stored data, cast to `T`. No type checks are done.
+    pub fn as_mut_ptr_of<T: Sized>(&mut self) -> *mut T {
+        self.as_mut_ptr() as *mut T
+    }
+
+    /// Stored data as a byte slice of [`UniBoxN::len`] bytes, e.g. to hash, checksum or persist it.
+    /// 
+    /// Pointers in the hosted value are only valid in the current process.
+    /// 
+    /// # Safety
+    /// 
+    /// Every byte of the hosted value must be initialized, see [`Uniboxed::as_bytes`](crate::Uniboxed::as_bytes).
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        slice::from_raw_parts(self.as_ptr(), self.len())
     }
 
     /// Stored data length.
     pub fn len(&self) -> usize {
-        self.len
+        self.len as usize
+    }
+
+    /// Size and alignment of the hosted type. An empty unibox has the layout of `()`.
+    /// 
+    /// The alignment of a unibox created with [`UniBoxN::from_bytes_with_id`] is unknown, and reported as 1.
+    pub fn layout(&self) -> Layout {
+        let align = self.type_meta.map_or(1, TypeMeta::align);
+        unsafe { Layout::from_size_align_unchecked(self.len(), align) }
+    }
+
+    /// Maximum size of a hosted type, the capacity of the buffer.
+    /// 
+    /// Lengths are stored in 32 bits, so it's capped to 4 GiB.
+    pub const fn capacity() -> usize {
+        if B::CAPACITY > u32::MAX as usize {
+            u32::MAX as usize
+        }
+        else {
+            B::CAPACITY
+        }
+    }
+
+    /// Maximum alignment of a hosted type, the alignment of the buffer.
+    pub const fn max_align() -> usize {
+        mem::align_of::<B>()
+    }
+
+    /// Bytes of the buffer not used by the hosted value.
+    pub fn free_space(&self) -> usize {
+        Self::capacity() - self.len()
     }
 
     /// Type identifier.
     pub fn id(&self) -> &'static str {
-        self.id
+        // Both parts come from a &'static str
+        unsafe { core::str::from_utf8_unchecked(slice::from_raw_parts(self.id_ptr.as_ptr(), self.id_len as usize)) }
+    }
+
+    /// Change the type identifier, e.g. to re-tag a unibox received from a generic producer. Does nothing if the unibox is empty.
+    /// 
+    /// Ids longer than 4 GiB are truncated, the length is stored in 32 bits.
+    pub fn set_id(&mut self, id: &'static str) {
+        if !self.is_empty() {
+            self.set_id_raw(id);
+            #[cfg(feature = "diagnostics")]
+            self.live.set(id, self.len());
+            #[cfg(feature = "stats")]
+            self.stats.set(id, self.len());
+        }
+    }
+
+    /// Change the type identifier, builder style. See [`UniBoxN::set_id`].
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.set_id(id);
+        self
+    }
+
+    /// User metadata word attached to the hosted value, 0 by default.
+    pub fn meta(&self) -> usize {
+        self.meta
+    }
+
+    /// Set the user metadata word. It's reset to 0 when the hosted value is dropped or moved out.
+    pub fn set_meta(&mut self, meta: usize) {
+        self.meta = meta;
+    }
+
+    /// Attach a user metadata word, e.g. flags or priority, builder style.
+    pub fn with_meta(mut self, meta: usize) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Register a hook called right before every hosted value is dropped, e.g. to log, flush, or return a pooled resource.
+    /// 
+    /// It isn't called when the value is moved out or forgotten.
+    pub fn set_on_drop(&mut self, hook: OnDropHook) {
+        self.on_drop = Some(hook);
+    }
+
+    /// Register a drop hook, builder style. See [`UniBoxN::set_on_drop`].
+    pub fn with_on_drop(mut self, hook: OnDropHook) -> Self {
+        self.set_on_drop(hook);
+        self
+    }
+
+    /// The hosted value as a [`Debug`] trait object, if the unibox was created with [`UniBoxN::new_debuggable`].
+    pub fn as_debug(&self) -> Option<&dyn Debug> {
+        self.type_meta?.debug.map(|_| self as &dyn Debug)
+    }
+
+    /// The hosted value as the trait object `D`, if the unibox was created with [`UniBoxN::new_dyn`] for `D`.
+    pub fn as_dyn<D: ?Sized + 'static>(&self) -> Option<&D> {
+        let cast = self.type_meta?.dyn_cast::<D>()?;
+        Some(unsafe { &*cast(self.as_ptr() as *mut u8) })
+    }
+
+    /// The hosted value as the mutable trait object `D`, if the unibox was created with [`UniBoxN::new_dyn`] for `D`.
+    pub fn as_dyn_mut<D: ?Sized + 'static>(&mut self) -> Option<&mut D> {
+        let cast = self.type_meta?.dyn_cast::<D>()?;
+        Some(unsafe { &mut *cast(self.as_mut_ptr()) })
+    }
+
+    /// Compare the hosted values of two uniboxes. See [`Uniboxed::eq_dyn`](crate::Uniboxed::eq_dyn).
+    pub fn eq_dyn(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return self.is_empty() && other.is_empty();
+        }
+        let eq = |unibox: &Self| unibox.hook(|type_meta| type_meta.eq);
+        let hashable = |unibox: &Self| unibox.hook(|type_meta| type_meta.hash).is_some();
+        // Without a hook on both sides a unibox is only equal to itself, and values that hash differently are never equal
+        core::ptr::eq(self, other) || match (eq(self), eq(other)) {
+            // Ids can be changed, only the TypeId proves that both values are of the type expected by the hook
+            (Some(eq), Some(_)) => self.id() == other.id() && self.hosted_type_id() == other.hosted_type_id() && hashable(self) == hashable(other) && eq(self.as_ptr(), other.as_ptr()),
+            _ => false
+        }
+    }
+
+    /// Order two uniboxes. See [`Uniboxed::cmp_dyn`](crate::Uniboxed::cmp_dyn).
+    pub fn cmp_dyn(&self, other: &Self) -> Ordering {
+        if self.is_empty() || other.is_empty() {
+            return other.is_empty().cmp(&self.is_empty());
+        }
+        self.id().cmp(other.id()).then(self.len.cmp(&other.len)).then_with(|| {
+            // Ids can be changed, the hook is only called if both values are of the same type and recorded it
+            let cmp = |unibox: &Self| unibox.hook(|type_meta| type_meta.cmp);
+            self.hosted_type_id().cmp(&other.hosted_type_id())
+                .then(cmp(self).is_some().cmp(&cmp(other).is_some()))
+                .then_with(|| cmp(self).map_or(Ordering::Equal, |cmp| cmp(self.as_ptr(), other.as_ptr())))
+        })
+    }
+
+    /// Compare the hosted data of two uniboxes in constant time. See [`Uniboxed::eq_ct`](crate::Uniboxed::eq_ct).
+    /// 
+    /// # Safety
+    /// 
+    /// Every byte of both hosted values must be initialized, see [`Uniboxed::as_bytes`](crate::Uniboxed::as_bytes).
+    pub unsafe fn eq_ct(&self, other: &Self) -> bool {
+        self.id() == other.id() && self.len == other.len && ct_eq_bytes(self.as_bytes(), other.as_bytes())
+    }
+
+    /// Copy the hosted value to a shared-memory segment. See [`Uniboxed::write_shared`](crate::Uniboxed::write_shared).
+    #[cfg(feature = "bytemuck")]
+    pub fn write_shared(&self, segment: &mut [u8]) -> Result<usize, SharedError> {
+        crate::shared::write_shared(self.type_meta, self.meta, self.as_ptr(), segment)
+    }
+
+    /// [`TypeId`] of the hosted type, only known if the unibox was created with [`UniBoxN::new_any`] or a constructor that compares values, like [`UniBoxN::new_eq`].
+    pub fn hosted_type_id(&self) -> Option<TypeId> {
+        self.type_meta.and_then(TypeMeta::type_id)
     }
 
-    unsafe fn as_owned<T: Sized>(&self) -> T {
-        ptr::read(self.data.ptr() as *const T)
+    /// Description of the hosted type, shared by all the uniboxes hosting it. See [`Uniboxed::type_meta`](crate::Uniboxed::type_meta).
+    pub fn type_meta(&self) -> Option<&'static TypeMeta> {
+        self.type_meta
+    }
+
+    /// Get reference to stored data if it is of type `T`.
+    /// 
+    /// Returns None if the unibox wasn't created with [`UniBoxN::new_any`] or the type is different.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            // The id may have been changed with set_id, the TypeId is enough
+            Some(unsafe { &*self.as_ptr_of::<T>() })
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get mutable reference to stored data if it is of type `T`.
+    /// 
+    /// Returns None if the unibox wasn't created with [`UniBoxN::new_any`] or the type is different.
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &mut *self.as_mut_ptr_of::<T>() })
+        }
+        else {
+            None
+        }
+    }
+
+    /// Move stored data out of the unibox if it is of type `T`, otherwise give the unibox back.
+    /// 
+    /// Returns Err if the unibox wasn't created with [`UniBoxN::new_any`] or the type is different.
+    #[allow(clippy::result_large_err)]
+    pub fn downcast<T: 'static>(mut self) -> Result<T, Self> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            let instance = unsafe { self.as_owned::<T>() };
+            self.clear();
+            Ok(instance)
+        }
+        else {
+            Err(self)
+        }
+    }
+
+    /// Create a new UniBox instance described by *type_meta*, the metadata of `T` recorded by a constructor.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_with_meta<T: 'static>(instance: T, type_meta: &'static TypeMeta) -> Result<Self, UniBoxError> {
+        let mut unibox = Self::new(instance)?;
+        unibox.type_meta = Some(type_meta);
+        Ok(unibox)
+    }
+
+    /// Optional hook of the hosted type, if any.
+    fn hook<F>(&self, f: impl FnOnce(&'static TypeMeta) -> Option<F>) -> Option<F> {
+        self.type_meta.and_then(f)
+    }
+
+    /// Store a value in the unibox, that must be empty. Returns the value back if it doesn't fit.
+    fn store<T: Sized>(&mut self, instance: T, id: &'static str) -> Result<(), T> {
+        if Self::fits::<T>().is_err() {
+            return Err(instance);
+        }
+        self.write(instance, id);
+        Ok(())
+    }
+
+    /// Check that a `T` fits in the buffer, both in size and alignment.
+    fn fits<T: Sized>() -> Result<(), UniBoxError> {
+        if mem::size_of::<T>() > Self::capacity() {
+            Err(UniBoxError::TooBig {
+                size: mem::size_of::<T>(),
+                capacity: Self::capacity()
+            })
+        }
+        else if mem::align_of::<T>() > mem::align_of::<B>() {
+            Err(UniBoxError::Misaligned {
+                align: mem::align_of::<T>(),
+                buffer_align: mem::align_of::<B>()
+            })
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Store a value in the unibox, that must be empty. The type must fit, see [`UniBoxN::fits`].
+    fn write<T: Sized>(&mut self, instance: T, id: &'static str) {
+        // A typed copy, padding bytes of T are never read
+        unsafe {
+            ptr::write(self.as_mut_ptr_of::<T>(), instance);
+        }
+        self.set_hosted(id, TypeMeta::of::<T>());
+    }
+
+    /// Record that the buffer hosts a value identified by *id*, of the type described by *type_meta*.
+    fn set_hosted(&mut self, id: &'static str, type_meta: &'static TypeMeta) {
+        self.set_hosted_raw(id, type_meta.size, type_meta);
+    }
+
+    /// Record that the buffer hosts *len* bytes of a value identified by *id*, of the type described by *type_meta*.
+    fn set_hosted_raw(&mut self, id: &'static str, len: usize, type_meta: &'static TypeMeta) {
+        self.type_meta = Some(type_meta);
+        self.set_id_raw(id);
+        // Checked against the capacity
+        self.len = len as u32;
+        #[cfg(feature = "diagnostics")]
+        self.live.set(id, len);
+        #[cfg(feature = "stats")]
+        self.stats.set(id, len);
+    }
+
+    /// Store the id, without checking that the unibox hosts a value.
+    fn set_id_raw(&mut self, id: &'static str) {
+        self.id_ptr = NonNull::from(id).cast();
+        // Truncate instead of panicking, at a char boundary so the id is still valid UTF-8
+        let mut len = id.len().min(u32::MAX as usize);
+        while !id.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.id_len = len as u32;
+    }
+
+    /// The hosted value, with its id and metadata.
+    pub(crate) fn hosted(&self) -> Hosted {
+        Hosted {
+            id: self.id(),
+            len: self.len(),
+            type_meta: self.type_meta,
+            meta: self.meta,
+            on_drop: self.on_drop
+        }
+    }
+
+    /// Record that the buffer, of an empty unibox, hosts the value described by *hosted*.
+    pub(crate) fn set_hosted_from(&mut self, hosted: Hosted) {
+        if let Some(type_meta) = hosted.type_meta {
+            self.set_hosted_raw(hosted.id, hosted.len, type_meta);
+        }
+        self.meta = hosted.meta;
+        self.on_drop = hosted.on_drop;
+    }
+
+    /// Drop the hosted value, if any, leaving the unibox empty.
+    fn drop_hosted(&mut self) {
+        if let Some(type_meta) = self.type_meta {
+            if let Some(on_drop) = self.on_drop {
+                on_drop(self);
+            }
+            #[cfg(feature = "zeroize")]
+            self.zeroize();
+            if let Some(drop_fn) = type_meta.drop_fn {
+                drop_fn(self.as_mut_ptr());
+            }
+            // A boxed value also owns its allocation
+            #[cfg(feature = "alloc")]
+            if self.boxed && type_meta.size != 0 {
+                unsafe { self.free_boxed(type_meta.layout()) };
+            }
+        }
+        self.clear();
+    }
+
+    /// Reset to the empty state, once the hosted value has been dropped or moved out.
+    pub(crate) fn clear(&mut self) {
+        #[cfg(feature = "zeroize")]
+        unsafe {
+            slice::from_raw_parts_mut(&mut self.data as *mut B as *mut u8, B::CAPACITY).zeroize();
+        }
+        #[cfg(feature = "poison")]
+        unsafe {
+            ptr::write_volatile(&mut self.data, B::init());
+        }
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        unsafe {
+            ptr::write_bytes(&mut self.data as *mut B as *mut u8, crate::uniboxed::POISON_BYTE, B::CAPACITY);
+        }
+        self.type_meta = None;
+        self.set_id_raw("");
+        self.len = 0;
+        self.meta = 0;
+        #[cfg(feature = "alloc")]
+        {
+            self.boxed = false;
+        }
+        #[cfg(feature = "diagnostics")]
+        self.live.set("", 0);
+        #[cfg(feature = "stats")]
+        self.stats.set("", 0);
+    }
+
+    /// Wipe and free the allocation of a boxed value, once it has been dropped or moved out, like the buffer in [`UniBoxN::clear`].
+    #[cfg(feature = "alloc")]
+    unsafe fn free_boxed(&mut self, layout: Layout) {
+        let boxed = self.as_mut_ptr();
+        #[cfg(feature = "zeroize")]
+        slice::from_raw_parts_mut(boxed, layout.size()).zeroize();
+        #[cfg(feature = "poison")]
+        for i in 0..layout.size() {
+            ptr::write_volatile(boxed.add(i), 0);
+        }
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        ptr::write_bytes(boxed, crate::uniboxed::POISON_BYTE, layout.size());
+        alloc::alloc::dealloc(boxed, layout);
+    }
+
+    unsafe fn as_owned<T: Sized>(&mut self) -> T {
+        #[cfg(feature = "alloc")]
+        if self.boxed {
+            let instance = ptr::read(self.as_ptr_of::<T>());
+            if mem::size_of::<T>() != 0 {
+                self.free_boxed(Layout::new::<T>());
+            }
+            return instance;
+        }
+        ptr::read(self.data.ptr::<T>())
     }
 
     pub fn check_type<T>(&self) -> bool {
         let len = mem::size_of::<T>();
         // Integrity checks
-        len == self.len && self.id == core::any::type_name::<T>()
+        len == self.len() && self.id() == core::any::type_name::<T>()
     }
 
     fn integrity_checks<T>(&self) {
@@ -103,6 +1056,65 @@ impl<B: Buffer> UniBoxN<B> {
 
 impl<S: Buffer> Drop for UniBoxN<S> {
     fn drop(&mut self) {
-        (self.autodrop)(self);
+        self.drop_hosted();
+    }
+}
+
+/// Prints the hosted value if the unibox was created with [`UniBoxN::new_debuggable`], otherwise its id and length.
+impl<B: Buffer> Debug for UniBoxN<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hook(|type_meta| type_meta.debug) {
+            Some(debug) => debug(self.as_ptr(), f),
+            None => f.debug_struct("UniBoxN").field("id", &self.id()).field("len", &self.len).finish_non_exhaustive()
+        }
+    }
+}
+
+/// Uses [`UniBoxN::eq_dyn`]. Uniboxes not created with [`UniBoxN::new_eq`] or [`UniBoxN::new_hashable`] are only equal to themselves.
+impl<B: Buffer> PartialEq for UniBoxN<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_dyn(other)
     }
 }
+
+impl<B: Buffer> Eq for UniBoxN<B> {}
+
+/// Hashes the id, and the hosted value if the unibox was created with [`UniBoxN::new_hashable`].
+impl<B: Buffer> Hash for UniBoxN<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+        if let Some(hash) = self.hook(|type_meta| type_meta.hash) {
+            hash(self.as_ptr(), state);
+        }
+    }
+}
+
+/// Panics if the unibox is not cloneable, see [`UniBoxN::try_clone`].
+impl<B: Buffer> Clone for UniBoxN<B> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Hosted value is not cloneable")
+    }
+}
+
+/// Empty unibox, see [`UniBoxN::empty`].
+impl<B: Buffer> Default for UniBoxN<B> {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// Wipes the hosted value in place if the unibox was created with [`UniBoxN::new_zeroizing`], otherwise it does nothing.
+#[cfg(feature = "zeroize")]
+impl<B: Buffer> Zeroize for UniBoxN<B> {
+    fn zeroize(&mut self) {
+        if let Some(zeroize) = self.hook(|type_meta| type_meta.zeroize) {
+            zeroize(self.as_mut_ptr());
+        }
+    }
+}
+
+/// The internal buffer is always wiped after the hosted value is dropped or moved out.
+#[cfg(feature = "zeroize")]
+impl<B: Buffer> ZeroizeOnDrop for UniBoxN<B> {}
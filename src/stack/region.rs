@@ -0,0 +1,266 @@
+use core::{
+    alloc::Layout,
+    any::TypeId,
+    marker::PhantomData,
+    mem,
+    ops::Drop,
+    ptr,
+    slice
+};
+use crate::{TypeMeta, TypeMetaOf, UniBoxError};
+#[cfg(feature = "diagnostics")]
+use crate::diagnostics::LiveToken;
+#[cfg(feature = "stats")]
+use crate::stats::StatsToken;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Unibox that stores its value in a memory region provided by the application, like a `static` array or a linker-placed section, instead of embedding the buffer.
+///
+/// Useful on heapless targets to host values too big for the stack, or to use hardware buffers as typed slots with [`UniBoxRegion::from_raw_parts`]. The region is borrowed for the whole life of the unibox, and can be recovered with [`UniBoxRegion::into_region`].
+///
+/// Since the region can't be created on demand, this type doesn't implement [`Uniboxed`](crate::Uniboxed), but it does implement [`DynUnibox`](crate::DynUnibox). Only `'static` types can be hosted, so the value is accessed safely with [`UniBoxRegion::downcast_ref`] and [`UniBoxRegion::downcast_mut`].
+pub struct UniBoxRegion {
+    ptr: *mut u8,
+    capacity: usize,
+    // Bytes of the region written by hosted values, that may have uninitialized padding
+    used: usize,
+    type_meta: Option<&'static TypeMeta>,
+    id: &'static str,
+    meta: usize,
+    _region: PhantomData<&'static mut [u8]>,
+    #[cfg(feature = "diagnostics")]
+    live: LiveToken,
+    #[cfg(feature = "stats")]
+    stats: StatsToken
+}
+
+impl UniBoxRegion {
+    /// Create an empty unibox over *region*.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new(region: &'static mut [u8]) -> Self {
+        Self {
+            ptr: region.as_mut_ptr(),
+            capacity: region.len(),
+            used: 0,
+            type_meta: None,
+            id: "",
+            meta: 0,
+            _region: PhantomData,
+            #[cfg(feature = "diagnostics")]
+            live: LiveToken::new("", 0),
+            #[cfg(feature = "stats")]
+            stats: StatsToken::new()
+        }
+    }
+
+    /// Create an empty unibox over a raw memory region, like a DMA-capable SRAM bank. The unibox handles drop and type bookkeeping but doesn't own the memory.
+    ///
+    /// # Safety
+    ///
+    /// *ptr* must be non-null and valid for reads and writes of *len* initialized bytes, and nothing else can access that memory while the unibox lives. If the region is recovered with [`UniBoxRegion::into_region`], this must hold for `'static`.
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub unsafe fn from_raw_parts(ptr: *mut u8, len: usize) -> Self {
+        Self::new(slice::from_raw_parts_mut(ptr, len))
+    }
+
+    /// Drop the hosted value, if any, and return the pointer and length of the region, zeroed like in [`UniBoxRegion::into_region`].
+    pub fn into_raw_parts(self) -> (*mut u8, usize) {
+        let region = self.into_region();
+        (region.as_mut_ptr(), region.len())
+    }
+
+    /// Drop the hosted value, if any, and give the region back.
+    ///
+    /// The bytes used by hosted values are zeroed, since their padding bytes may be uninitialized. With the `debug-poison` feature they keep the poison pattern instead.
+    pub fn into_region(mut self) -> &'static mut [u8] {
+        self.clear();
+        // The poison pattern already overwrote every byte used
+        #[cfg(not(all(feature = "debug-poison", debug_assertions)))]
+        unsafe {
+            ptr::write_bytes(self.ptr, 0, self.used);
+        }
+        // The unibox is empty, so it won't touch the region anymore
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.capacity) }
+    }
+
+    /// Check that a `T` fits in the region, both in size and alignment.
+    pub fn fits<T: Sized>(&self) -> Result<(), UniBoxError> {
+        if mem::size_of::<T>() > self.capacity {
+            Err(UniBoxError::TooBig {
+                size: mem::size_of::<T>(),
+                capacity: self.capacity
+            })
+        }
+        else if self.ptr as usize & (mem::align_of::<T>() - 1) != 0 {
+            Err(UniBoxError::Misaligned {
+                align: mem::align_of::<T>(),
+                buffer_align: 1 << (self.ptr as usize).trailing_zeros()
+            })
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Put a new value in the unibox.
+    ///
+    /// If the hosted type is the same, the value is swapped and the old one returned. Otherwise the hosted value, if any, is dropped.
+    ///
+    /// Returns Err with the new value if it doesn't fit in the region, see [`UniBoxRegion::fits`]. The hosted value is kept in that case.
+    pub fn replace<T: 'static>(&mut self, instance: T) -> Result<Option<T>, T> {
+        if let Some(hosted) = self.downcast_mut::<T>() {
+            Ok(Some(mem::replace(hosted, instance)))
+        }
+        else if self.fits::<T>().is_err() {
+            Err(instance)
+        }
+        else {
+            self.clear();
+            unsafe {
+                ptr::write(self.ptr as *mut T, instance);
+            }
+            self.type_meta = Some(&TypeMetaOf::<T>::ANY);
+            self.id = core::any::type_name::<T>();
+            self.used = self.used.max(mem::size_of::<T>());
+            #[cfg(feature = "diagnostics")]
+            self.live.set(self.id, mem::size_of::<T>());
+            #[cfg(feature = "stats")]
+            self.stats.set(self.id, mem::size_of::<T>());
+            Ok(None)
+        }
+    }
+
+    /// Move the hosted value out if it is of type `T`, leaving the unibox empty.
+    pub fn take<T: 'static>(&mut self) -> Option<T> {
+        if self.hosted_type_id() != Some(TypeId::of::<T>()) {
+            return None;
+        }
+        let instance = unsafe { ptr::read(self.ptr as *const T) };
+        self.forget();
+        Some(instance)
+    }
+
+    /// Drop the hosted value, if any, leaving the unibox empty.
+    pub fn clear(&mut self) {
+        if let Some(drop_fn) = self.type_meta.and_then(|type_meta| type_meta.drop_fn) {
+            drop_fn(self.ptr);
+        }
+        self.forget();
+    }
+
+    /// Reset to the empty state, once the hosted value has been dropped or moved out.
+    fn forget(&mut self) {
+        #[cfg(feature = "zeroize")]
+        unsafe {
+            slice::from_raw_parts_mut(self.ptr, self.len()).zeroize();
+        }
+        #[cfg(feature = "poison")]
+        unsafe {
+            ptr::write_bytes(self.ptr, 0, self.len());
+        }
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        unsafe {
+            ptr::write_bytes(self.ptr, crate::uniboxed::POISON_BYTE, self.len());
+        }
+        self.type_meta = None;
+        self.id = "";
+        self.meta = 0;
+        #[cfg(feature = "diagnostics")]
+        self.live.set("", 0);
+        #[cfg(feature = "stats")]
+        self.stats.set("", 0);
+    }
+
+    /// Get reference to stored data if it is of type `T`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &*(self.ptr as *const T) })
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get mutable reference to stored data if it is of type `T`.
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &mut *(self.ptr as *mut T) })
+        }
+        else {
+            None
+        }
+    }
+
+    /// The unibox hosts no value.
+    pub fn is_empty(&self) -> bool {
+        self.type_meta.is_none()
+    }
+
+    /// Type identifier.
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    /// Stored data length.
+    pub fn len(&self) -> usize {
+        self.type_meta.map_or(0, TypeMeta::size)
+    }
+
+    /// Size and alignment of the hosted type. An empty unibox has the layout of `()`.
+    pub fn layout(&self) -> Layout {
+        self.type_meta.map_or(Layout::new::<()>(), TypeMeta::layout)
+    }
+
+    /// Maximum size of a hosted type, the length of the region.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// [`TypeId`] of the hosted type.
+    pub fn hosted_type_id(&self) -> Option<TypeId> {
+        self.type_meta.and_then(TypeMeta::type_id)
+    }
+
+    /// Description of the hosted type. See [`Uniboxed::type_meta`](crate::Uniboxed::type_meta).
+    pub fn type_meta(&self) -> Option<&'static TypeMeta> {
+        self.type_meta
+    }
+
+    /// User metadata word attached to the hosted value. Reset when the value is dropped.
+    pub fn meta(&self) -> usize {
+        self.meta
+    }
+
+    /// Set the user metadata word, e.g. a version number or a small tag. It's reset to 0 when the hosted value is dropped or moved out.
+    pub fn set_meta(&mut self, meta: usize) {
+        self.meta = meta;
+    }
+
+    /// Raw pointer to stored data.
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    /// Raw mutable pointer to stored data.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Stored data as a byte slice of [`UniBoxRegion::len`] bytes.
+    ///
+    /// Pointers in the hosted value are only valid in the current process.
+    ///
+    /// # Safety
+    ///
+    /// Every byte of the hosted value must be initialized, see [`Uniboxed::as_bytes`](crate::Uniboxed::as_bytes).
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        slice::from_raw_parts(self.ptr, self.len())
+    }
+}
+
+impl Drop for UniBoxRegion {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
@@ -1,82 +1,255 @@
-use super::super::Uniboxed;
-use super::UniBoxN;
+use core::{
+    alloc::Layout,
+    cmp::Ordering,
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    pin::Pin
+};
+use super::super::{AccessError, CloneUniboxed, DynCast, OnDropHook, TypeMeta, Uniboxed, UniBoxError};
+use super::{Align, AlignedBytes, SupportedAlign, UniBoxN};
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use crate::UniBox;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "bytemuck")]
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "bytemuck")]
+use crate::SharedError;
 
-/// Store a type on stack with a max size of 32 bytes.
-pub struct UniBox32 {
-    unibox: UniBoxN<[u8; 32]>
+/// Store a type on stack with a max size of N bytes, aligned to A bytes.
+/// 
+/// For values that need a bigger alignment than [`UniBoxSized`], like SIMD types, cache-line-sensitive structs or DMA descriptors. A must be a power of two up to 4096.
+/// 
+/// It has the layout of a [`UniBoxN`] with an [`AlignedBytes`] buffer, see [`UniBoxN#layout`].
+#[repr(transparent)]
+pub struct UniBoxAligned<const N: usize, const A: usize> where Align<A>: SupportedAlign {
+    unibox: UniBoxN<AlignedBytes<N, A>>
 }
 
-impl Uniboxed for UniBox32 {
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized {
+impl<const N: usize, const A: usize> UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    /// Create a new unibox in const context, e.g. to initialize a `static`. See [`UniBoxN::new_const`].
+    pub const fn new_const<T: Copy>(instance: T, id: &'static str) -> Self {
+        Self {
+            unibox: UniBoxN::new_const(instance, id)
+        }
+    }
+
+    /// Maximum size of a hosted type, N bytes.
+    pub const fn capacity() -> usize {
+        UniBoxN::<AlignedBytes<N, A>>::capacity()
+    }
+
+    /// Maximum alignment of a hosted type, A bytes.
+    pub const fn max_align() -> usize {
+        UniBoxN::<AlignedBytes<N, A>>::max_align()
+    }
+
+    /// Bytes of the buffer not used by the hosted value.
+    pub fn free_space(&self) -> usize {
+        self.unibox.free_space()
+    }
+
+    /// Get pinned reference to stored data using a type. See [`UniBoxN::as_pin`].
+    /// 
+    /// # Safety
+    /// 
+    /// See [`UniBoxN::as_pin`].
+    pub unsafe fn as_pin<T: Sized>(self: Pin<&Self>) -> Pin<&T> {
+        self.map_unchecked(|ubox| &ubox.unibox).as_pin()
+    }
+
+    /// Get pinned mutable reference to stored data using a type. See [`UniBoxN::as_pin_mut`].
+    /// 
+    /// # Safety
+    /// 
+    /// See [`UniBoxN::as_pin_mut`].
+    pub unsafe fn as_pin_mut<T: Sized>(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.map_unchecked_mut(|ubox| &mut ubox.unibox).as_pin_mut()
+    }
+
+    /// Create a new unibox that wipes the hosted value on drop. See [`UniBoxN::new_zeroizing`].
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroizing<T: Zeroize + 'static>(instance: T) -> Result<Self, UniBoxError> {
         Ok(
             Self {
-                unibox: UniBoxN::new(instance)?
+                unibox: UniBoxN::new_zeroizing(instance)?
             }
         )
     }
 
-    unsafe fn as_ref<T: Sized>(&self) -> &T {
-        self.unibox.as_ref()
+    /// Create a new unibox hosting secret material, wiped after the hosted value is dropped. See [`UniBoxN::new_secret`].
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_secret<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_secret(instance)?
+            }
+        )
     }
 
-    unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
-        self.unibox.as_mut_ref()
+    /// Move the hosted value, with its id, metadata and hooks, to a heap unibox. See [`UniBoxN::to_heap`].
+    /// 
+    /// Returns Err with the unibox if the allocation fails.
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::result_large_err, clippy::wrong_self_convention)]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn to_heap(self) -> Result<UniBox, Self> {
+        self.unibox.to_heap().map_err(|unibox| Self { unibox })
     }
 
-    fn len(&self) -> usize {
-        self.unibox.len()
+    /// Move the hosted value to a static unibox of a different size or alignment, usually a bigger one. See [`UniBoxN::upsize`].
+    /// 
+    /// Returns Err with the unibox if the hosted value doesn't fit in the new one.
+    /// 
+    /// ```
+    /// use unibox::{ Uniboxed, UniBox32, UniBox64 };
+    /// 
+    /// let small = UniBox32::new(10u32).expect("Couldn't create UniBox32");
+    /// let big: UniBox64 = small.upsize().expect("Couldn't upsize");
+    /// assert_eq!(big.id(), "u32");
+    /// ```
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    #[allow(clippy::result_large_err)]
+    pub fn upsize<const M: usize, const B: usize>(self) -> Result<UniBoxAligned<M, B>, Self> where Align<B>: SupportedAlign {
+        match self.unibox.upsize() {
+            Ok(unibox) => Ok(UniBoxAligned { unibox }),
+            Err(unibox) => Err(Self { unibox })
+        }
     }
 
-    fn id(&self) -> &'static str {
-        self.unibox.id()
+    /// Create a new unibox, or if the value doesn't fit, host it in a `Box`. See [`UniBoxN::new_or_boxed`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_or_boxed<T: 'static>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_or_boxed(instance)?
+            }
+        )
     }
-}
 
-/// Store a type on stack with a max size of 64 bytes.
-pub struct UniBox64 {
-    unibox: UniBoxN<[u8; 64]>
-}
+    /// The hosted value was moved to the heap by [`UniBoxAligned::new_or_boxed`].
+    #[cfg(feature = "alloc")]
+    pub fn is_boxed(&self) -> bool {
+        self.unibox.is_boxed()
+    }
 
-impl Uniboxed for UniBox64 {
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized {
+    /// Create a new unibox hosting a value that can be accessed as a trait object. See [`UniBoxN::new_dyn`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_dyn<C: DynCast<T>, T: 'static>(instance: T) -> Result<Self, UniBoxError> {
         Ok(
             Self {
-                unibox: UniBoxN::new(instance)?
+                unibox: UniBoxN::new_dyn::<C, T>(instance)?
             }
         )
     }
 
-    unsafe fn as_ref<T: Sized>(&self) -> &T {
-        self.unibox.as_ref()
+    /// Create a new unibox hosting a zeroed `T`. See [`UniBoxN::new_zeroed`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_zeroed<T: Zeroable + 'static>() -> Result<Self, UniBoxError> {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_zeroed::<T>()?
+            }
+        )
     }
 
-    unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
-        self.unibox.as_mut_ref()
+    /// Create a new unibox hosting a Pod value, cloned by copying its bytes. See [`UniBoxN::new_pod`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_pod<T: Pod>(instance: T) -> Result<Self, UniBoxError> {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_pod(instance)?
+            }
+        )
     }
 
-    fn len(&self) -> usize {
-        self.unibox.len()
+    /// Rebuild a unibox hosting a Pod value written to a shared-memory segment. See [`UniBoxN::from_shared`].
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn from_shared<T: Pod>(segment: &[u8]) -> Result<Self, SharedError> {
+        Ok(
+            Self {
+                unibox: UniBoxN::from_shared::<T>(segment)?
+            }
+        )
     }
+}
 
-    fn id(&self) -> &'static str {
-        self.unibox.id()
+impl<const N: usize, const A: usize> Uniboxed for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    unsafe fn new_unbounded<T: Sized>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_unbounded(instance)?
+            }
+        )
     }
-}
 
-/// Store a type on stack with a max size of 128 bytes.
-pub struct UniBox128 {
-    unibox: UniBoxN<[u8; 128]>
-}
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_any(instance)?
+            }
+        )
+    }
 
-impl Uniboxed for UniBox128 {
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_eq<T: PartialEq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
         Ok(
             Self {
-                unibox: UniBoxN::new(instance)?
+                unibox: UniBoxN::new_eq(instance)?
             }
         )
     }
 
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_hashable<T: Hash + Eq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_hashable(instance)?
+            }
+        )
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_ord<T: Ord + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_ord(instance)?
+            }
+        )
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_debuggable<T: Debug + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(
+            Self {
+                unibox: UniBoxN::new_debuggable(instance)?
+            }
+        )
+    }
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn empty() -> Self {
+        Self {
+            unibox: UniBoxN::empty()
+        }
+    }
+
     unsafe fn as_ref<T: Sized>(&self) -> &T {
         self.unibox.as_ref()
     }
@@ -85,42 +258,348 @@ impl Uniboxed for UniBox128 {
         self.unibox.as_mut_ref()
     }
 
+    unsafe fn take<T: Sized>(&mut self) -> T {
+        self.unibox.take()
+    }
+
+    unsafe fn try_as_ref<T: Sized>(&self) -> Result<&T, AccessError> {
+        self.unibox.try_as_ref()
+    }
+
+    unsafe fn try_as_mut_ref<T: Sized>(&mut self) -> Result<&mut T, AccessError> {
+        self.unibox.try_as_mut_ref()
+    }
+
+    unsafe fn try_take<T: Sized>(&mut self) -> Result<T, AccessError> {
+        self.unibox.try_take()
+    }
+
+    fn forget_inner(self) {
+        self.unibox.forget_inner()
+    }
+
+    unsafe fn replace<T: Sized>(&mut self, instance: T) -> Result<Option<T>, T> {
+        self.unibox.replace(instance)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.unibox.is_empty()
+    }
+
+    fn as_ptr(&self) -> *const u8 {
+        self.unibox.as_ptr()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.unibox.as_mut_ptr()
+    }
+
     fn len(&self) -> usize {
         self.unibox.len()
     }
 
+    fn layout(&self) -> Layout {
+        self.unibox.layout()
+    }
+
     fn id(&self) -> &'static str {
         self.unibox.id()
     }
-}
 
-/// Store a type on stack with a max size of 256 bytes.
-pub struct UniBox256 {
-    unibox: UniBoxN<[u8; 256]>
+    fn set_id(&mut self, id: &'static str) {
+        self.unibox.set_id(id)
+    }
+
+    fn type_meta(&self) -> Option<&'static TypeMeta> {
+        self.unibox.type_meta()
+    }
+
+    fn meta(&self) -> usize {
+        self.unibox.meta()
+    }
+
+    fn set_meta(&mut self, meta: usize) {
+        self.unibox.set_meta(meta)
+    }
+
+    fn set_on_drop(&mut self, hook: OnDropHook) {
+        self.unibox.set_on_drop(hook)
+    }
+
+    fn cmp_dyn(&self, other: &Self) -> Ordering {
+        self.unibox.cmp_dyn(&other.unibox)
+    }
+
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        self.unibox.as_debug()
+    }
+
+    fn eq_dyn(&self, other: &Self) -> bool {
+        self.unibox.eq_dyn(&other.unibox)
+    }
 }
 
-impl Uniboxed for UniBox256 {
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized {
+impl<const N: usize, const A: usize> CloneUniboxed for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_cloneable<T: Clone + 'static>(instance: T) -> Result<Self, UniBoxError> {
         Ok(
             Self {
-                unibox: UniBoxN::new(instance)?
+                unibox: UniBoxN::new_cloneable(instance)?
             }
         )
     }
 
-    unsafe fn as_ref<T: Sized>(&self) -> &T {
-        self.unibox.as_ref()
+    fn is_cloneable(&self) -> bool {
+        self.unibox.is_cloneable()
     }
 
-    unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T {
-        self.unibox.as_mut_ref()
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn try_clone(&self) -> Option<Self> {
+        Some(
+            Self {
+                unibox: self.unibox.try_clone()?
+            }
+        )
     }
+}
 
-    fn len(&self) -> usize {
-        self.unibox.len()
+/// Prints the hosted value if the unibox was created with [`Uniboxed::new_debuggable`], otherwise its id and length.
+impl<const N: usize, const A: usize> Debug for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unibox.as_debug() {
+            Some(debug) => debug.fmt(f),
+            None => f.debug_struct("UniBoxAligned").field("id", &self.id()).field("len", &self.len()).finish_non_exhaustive()
+        }
     }
+}
 
-    fn id(&self) -> &'static str {
-        self.unibox.id()
+/// Uses [`Uniboxed::eq_dyn`]. Uniboxes not created with [`Uniboxed::new_eq`] or [`Uniboxed::new_hashable`] are only equal to themselves.
+impl<const N: usize, const A: usize> PartialEq for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    fn eq(&self, other: &Self) -> bool {
+        self.unibox == other.unibox
+    }
+}
+
+impl<const N: usize, const A: usize> Eq for UniBoxAligned<N, A> where Align<A>: SupportedAlign {}
+
+/// Hashes the id, and the hosted value if the unibox was created with [`Uniboxed::new_hashable`].
+impl<const N: usize, const A: usize> Hash for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unibox.hash(state);
+    }
+}
+
+/// Panics if the unibox is not cloneable, see [`CloneUniboxed::try_clone`].
+impl<const N: usize, const A: usize> Clone for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn clone(&self) -> Self {
+        Self {
+            unibox: self.unibox.clone()
+        }
+    }
+}
+
+/// Empty unibox, see [`Uniboxed::empty`].
+impl<const N: usize, const A: usize> Default for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize, const A: usize> Zeroize for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    fn zeroize(&mut self) {
+        self.unibox.zeroize();
     }
 }
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize, const A: usize> ZeroizeOnDrop for UniBoxAligned<N, A> where Align<A>: SupportedAlign {}
+
+/// Store a type on stack with a max size of N bytes.
+/// 
+/// Any capacity can be used, like `UniBoxSized<48>`. Common sizes have aliases, from [`UniBox8`] to [`UniBox4096`].
+pub type UniBoxSized<const N: usize> = UniBoxAligned<N, 16>;
+
+/// Store a type on stack with a max size of 8 bytes.
+/// 
+/// Aligned to 8 bytes, that is enough for any type that fits, so it takes no padding.
+pub type UniBox8 = UniBoxAligned<8, 8>;
+
+/// Store a type on stack with a max size of 16 bytes.
+pub type UniBox16 = UniBoxSized<16>;
+
+/// Store a type on stack with a max size of 32 bytes.
+pub type UniBox32 = UniBoxSized<32>;
+
+/// Store a type on stack with a max size of 64 bytes.
+pub type UniBox64 = UniBoxSized<64>;
+
+/// Store a type on stack with a max size of 128 bytes.
+pub type UniBox128 = UniBoxSized<128>;
+
+/// Store a type on stack with a max size of 256 bytes.
+pub type UniBox256 = UniBoxSized<256>;
+
+/// Store a type on stack with a max size of 512 bytes.
+pub type UniBox512 = UniBoxSized<512>;
+
+/// Store a type on stack with a max size of 1024 bytes.
+pub type UniBox1024 = UniBoxSized<1024>;
+
+/// Store a type on stack with a max size of 4096 bytes.
+pub type UniBox4096 = UniBoxSized<4096>;
+
+/// Capacities of the static unibox aliases, from [`UniBox8`] to [`UniBox4096`]. Shared by [`smallest_unibox!`] and [`UniBoxError::suggested_capacity`](crate::UniBoxError::suggested_capacity).
+pub(crate) const SIZE_CLASSES: [usize; 9] = [8, 16, 32, 64, 128, 256, 512, 1024, 4096];
+
+/// Moves the hosted value to a static unibox, see [`UniBox::try_to_static`]. Returns Err with the unibox if it doesn't fit.
+#[cfg(feature = "alloc")]
+impl<const N: usize, const A: usize> TryFrom<UniBox> for UniBoxAligned<N, A> where Align<A>: SupportedAlign {
+    type Error = UniBox;
+
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn try_from(unibox: UniBox) -> Result<Self, UniBox> {
+        Ok(
+            Self {
+                unibox: UniBoxN::try_from(unibox)?
+            }
+        )
+    }
+}
+
+/// Implement [`From`] for the widening conversions between static unibox aliases, see [`UniBoxAligned::upsize`].
+macro_rules! upsize_from {
+    ($small:ident => $($big:ident),*) => {
+        $(
+            /// Moves the hosted value to a bigger unibox.
+            impl From<$small> for $big {
+                #[cfg_attr(feature = "diagnostics", track_caller)]
+                fn from(unibox: $small) -> Self {
+                    match unibox.upsize() {
+                        Ok(unibox) => unibox,
+                        Err(_) => unreachable!("Value doesn't fit in a bigger unibox")
+                    }
+                }
+            }
+        )*
+    };
+}
+
+upsize_from!(UniBox8 => UniBox16, UniBox32, UniBox64, UniBox128, UniBox256, UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox16 => UniBox32, UniBox64, UniBox128, UniBox256, UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox32 => UniBox64, UniBox128, UniBox256, UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox64 => UniBox128, UniBox256, UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox128 => UniBox256, UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox256 => UniBox512, UniBox1024, UniBox4096);
+upsize_from!(UniBox512 => UniBox1024, UniBox4096);
+upsize_from!(UniBox1024 => UniBox4096);
+
+/// Define a static unibox type with a custom capacity, and optionally alignment.
+/// 
+/// Expands to an alias of [`UniBoxSized`], or [`UniBoxAligned`] if the alignment is given, that implement [`Uniboxed`] and the rest of traits for any size.
+/// 
+/// ```
+/// use unibox::{ define_unibox, Uniboxed };
+/// 
+/// define_unibox!(
+///     /// Unibox for AST nodes.
+///     pub NodeBox, 300
+/// );
+/// define_unibox!(DescriptorBox, 64, 64);
+/// 
+/// let node = NodeBox::new([0u8; 300]).expect("Couldn't create NodeBox");
+/// assert_eq!(node.len(), 300);
+/// assert_eq!(DescriptorBox::max_align(), 64);
+/// ```
+#[macro_export]
+macro_rules! define_unibox {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $size:expr) => {
+        $(#[$attr])*
+        $vis type $name = $crate::UniBoxSized<{ $size }>;
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident, $size:expr, $align:expr) => {
+        $(#[$attr])*
+        $vis type $name = $crate::UniBoxAligned<{ $size }, { $align }>;
+    };
+}
+
+/// Assert at compile time that a type fits in a static unibox, both in size and alignment.
+/// 
+/// Works with any unibox that has the const functions `capacity()` and `max_align()`, like [`UniBoxN`](crate::UniBoxN) and [`UniBoxAligned`]. Turns the [`UniBoxError`](crate::UniBoxError) returned by `new` into a build error when the type is known.
+/// 
+/// ```
+/// use unibox::{ unibox_fits, Uniboxed, UniBox64 };
+/// 
+/// struct Point(f64, f64, f64);
+/// unibox_fits!(Point, UniBox64);
+/// 
+/// let ubox = UniBox64::new(Point(1.0, 2.0, 3.0)).expect("Always fits");
+/// ```
+/// 
+/// ```compile_fail
+/// use unibox::{ unibox_fits, UniBox32 };
+/// 
+/// unibox_fits!([u8; 64], UniBox32);
+/// ```
+#[macro_export]
+macro_rules! unibox_fits {
+    ($t:ty, $u:ty) => {
+        const _: () = {
+            assert!(
+                ::core::mem::size_of::<$t>() <= <$u>::capacity(),
+                concat!(stringify!($t), " is too big for ", stringify!($u))
+            );
+            assert!(
+                ::core::mem::align_of::<$t>() <= <$u>::max_align(),
+                concat!(stringify!($t), " is misaligned for ", stringify!($u))
+            );
+        };
+    };
+}
+
+/// Capacity of the smallest static unibox alias that fits *size* bytes, used by [`smallest_unibox!`].
+#[doc(hidden)]
+pub const fn __size_class(size: usize) -> usize {
+    let mut i = 0;
+    while i < SIZE_CLASSES.len() {
+        if size <= SIZE_CLASSES[i] {
+            return SIZE_CLASSES[i];
+        }
+        i += 1;
+    }
+    panic!("Type too big for any static unibox")
+}
+
+/// Alignment of the static unibox alias of *capacity* that fits *align*, used by [`smallest_unibox!`]. [`UniBox8`] is aligned to 8 bytes, the other aliases to 16.
+#[doc(hidden)]
+pub const fn __align_class(capacity: usize, align: usize) -> usize {
+    let class = if capacity == 8 { 8 } else { 16 };
+    if align < class { class } else { align }
+}
+
+/// Resolve at compile time to the smallest static unibox that fits a type.
+/// 
+/// Picks the capacity among [`UniBox8`], [`UniBox16`], [`UniBox32`], [`UniBox64`], [`UniBox128`], [`UniBox256`], [`UniBox512`], [`UniBox1024`] and [`UniBox4096`], so the result is one of those types. Types more aligned than the alias get a [`UniBoxAligned`] with their alignment. Fails to compile if the type is bigger than 4096 bytes.
+/// 
+/// The type must be concrete, generic parameters can't be used in const expressions.
+/// 
+/// ```
+/// use unibox::{ smallest_unibox, Uniboxed, UniBox64 };
+/// 
+/// struct Point(f64, f64, f64, f64, f64);
+/// 
+/// let ubox: UniBox64 = <smallest_unibox!(Point)>::new(Point(1.0, 2.0, 3.0, 4.0, 5.0)).expect("Always fits");
+/// assert_eq!(<smallest_unibox!([u8; 100])>::capacity(), 128);
+/// assert_eq!(<smallest_unibox!(u32)>::capacity(), 8);
+/// ```
+#[macro_export]
+macro_rules! smallest_unibox {
+    ($t:ty) => {
+        $crate::UniBoxAligned<
+            { $crate::__size_class(::core::mem::size_of::<$t>()) },
+            { $crate::__align_class($crate::__size_class(::core::mem::size_of::<$t>()), ::core::mem::align_of::<$t>()) }
+        >
+    };
+}
@@ -0,0 +1,146 @@
+use core::{
+    cell::UnsafeCell,
+    hint,
+    ops::Drop,
+    sync::atomic::{
+        AtomicBool,
+        AtomicUsize,
+        Ordering
+    }
+};
+
+/// Max number of distinct ids counted by [`stats_by_id`]. Values with other ids are only counted in the totals.
+pub const STATS_IDS: usize = 64;
+
+/// Snapshot of the global unibox counters, see [`stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Uniboxes currently alive, empty ones included.
+    pub live: usize,
+    /// Uniboxes created since the program started.
+    pub created: usize,
+    /// Bytes of the values currently hosted by live uniboxes.
+    pub bytes: usize,
+    /// Highest value reached by `bytes`.
+    pub peak_bytes: usize
+}
+
+struct Counters {
+    live: AtomicUsize,
+    created: AtomicUsize,
+    bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    lock: AtomicBool,
+    ids: UnsafeCell<[Option<(&'static str, usize)>; STATS_IDS]>
+}
+
+unsafe impl Sync for Counters {}
+
+impl Counters {
+    fn with_ids<R>(&self, f: impl FnOnce(&mut [Option<(&'static str, usize)>; STATS_IDS]) -> R) -> R {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            hint::spin_loop();
+        }
+        let result = f(unsafe { &mut *self.ids.get() });
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+
+    fn add(&self, id: &'static str, len: usize) {
+        let bytes = self.bytes.fetch_add(len, Ordering::Relaxed) + len;
+        self.peak_bytes.fetch_max(bytes, Ordering::Relaxed);
+        if !id.is_empty() {
+            self.with_ids(|ids| {
+                if let Some(count) = ids.iter_mut().flatten().find(|(counted, _)| *counted == id).map(|(_, count)| count) {
+                    *count += 1;
+                }
+                else if let Some(slot) = ids.iter_mut().find(|slot| slot.is_none()) {
+                    *slot = Some((id, 1));
+                }
+            });
+        }
+    }
+
+    fn remove(&self, id: &'static str, len: usize) {
+        self.bytes.fetch_sub(len, Ordering::Relaxed);
+        if !id.is_empty() {
+            self.with_ids(|ids| {
+                for slot in ids.iter_mut() {
+                    match slot {
+                        Some((counted, 1)) if *counted == id => *slot = None,
+                        Some((counted, count)) if *counted == id => *count -= 1,
+                        _ => continue
+                    }
+                    break;
+                }
+            });
+        }
+    }
+}
+
+static COUNTERS: Counters = Counters {
+    live: AtomicUsize::new(0),
+    created: AtomicUsize::new(0),
+    bytes: AtomicUsize::new(0),
+    peak_bytes: AtomicUsize::new(0),
+    lock: AtomicBool::new(false),
+    ids: UnsafeCell::new([None; STATS_IDS])
+};
+
+/// Share of a unibox in the global counters, removed when dropped.
+pub(crate) enum StatsToken {
+    /// Counted, with the id and length of the hosted value.
+    Counted(&'static str, usize),
+    /// Created in const context, not counted.
+    Const
+}
+
+impl StatsToken {
+    pub(crate) fn new() -> Self {
+        COUNTERS.live.fetch_add(1, Ordering::Relaxed);
+        COUNTERS.created.fetch_add(1, Ordering::Relaxed);
+        Self::Counted("", 0)
+    }
+
+    /// Token of a unibox created in const context, that can't be counted.
+    pub(crate) const fn new_const() -> Self {
+        Self::Const
+    }
+
+    pub(crate) fn set(&mut self, id: &'static str, len: usize) {
+        if let Self::Counted(counted_id, counted_len) = self {
+            COUNTERS.remove(counted_id, *counted_len);
+            COUNTERS.add(id, len);
+            *counted_id = id;
+            *counted_len = len;
+        }
+    }
+}
+
+impl Drop for StatsToken {
+    fn drop(&mut self) {
+        if let Self::Counted(id, len) = *self {
+            COUNTERS.remove(id, len);
+            COUNTERS.live.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Snapshot of the global counters of uniboxes.
+///
+/// The counters are updated independently, so a snapshot taken while other threads create or drop uniboxes may be slightly inconsistent.
+pub fn stats() -> Stats {
+    Stats {
+        live: COUNTERS.live.load(Ordering::Relaxed),
+        created: COUNTERS.created.load(Ordering::Relaxed),
+        bytes: COUNTERS.bytes.load(Ordering::Relaxed),
+        peak_bytes: COUNTERS.peak_bytes.load(Ordering::Relaxed)
+    }
+}
+
+/// Snapshot of the number of hosted values per id, for the first [`STATS_IDS`] ids seen. Empty uniboxes are not counted.
+///
+/// It spins forever if called from a panic that happened while a unibox was being created or dropped.
+pub fn stats_by_id() -> [Option<(&'static str, usize)>; STATS_IDS] {
+    COUNTERS.with_ids(|ids| *ids)
+}
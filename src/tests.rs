@@ -1,6 +1,7 @@
 use crate::{
-    Uniboxed, UniBox32, UniBox64, UniBox128, UniBox256, UniBox
+    AccessError, AutoUniBox, CloneUniboxed, SyncUniBox, UniBoxError, UniBoxN, Uniboxed, UniBox8, UniBox16, UniBox32, UniBox64, UniBox128, UniBox256, UniBox512, UniBox1024, UniBox4096, UniBox, set_alloc_error_hook, set_alloc_retries
 };
+use core::mem::MaybeUninit;
 
 fn check_sucession(arr: &[u8]) -> bool {
     for (i, v) in arr.iter().enumerate() {
@@ -22,6 +23,7 @@ trait TestArrayStruct {
     fn check(&self) -> bool;
 }
 
+#[derive(Debug, Clone)]
 struct Test32([u8; 32]);
 impl TestArrayStruct for Test32 {
     fn new() -> Self {
@@ -35,6 +37,7 @@ impl TestArrayStruct for Test32 {
     }
 }
 
+#[derive(Debug, Clone)]
 struct Test64([u8; 64]);
 impl TestArrayStruct for Test64 {
     fn new() -> Self {
@@ -48,6 +51,7 @@ impl TestArrayStruct for Test64 {
     }
 }
 
+#[derive(Debug, Clone)]
 struct Test128([u8; 128]);
 impl TestArrayStruct for Test128 {
     fn new() -> Self {
@@ -61,6 +65,7 @@ impl TestArrayStruct for Test128 {
     }
 }
 
+#[derive(Debug, Clone)]
 struct Test256([u8; 256]);
 impl TestArrayStruct for Test256 {
     fn new() -> Self {
@@ -74,7 +79,7 @@ impl TestArrayStruct for Test256 {
     }
 }
 
-fn test_type<T: TestArrayStruct, U: Uniboxed>() {
+fn test_type<T: TestArrayStruct + 'static, U: Uniboxed>() {
     let ubox = U::new(T::new()).expect("Couldn't create a uniboxed type");
     let inner = unsafe { ubox.as_ref::<T>() };
     assert!(inner.check(), "Content is incorrect");
@@ -106,6 +111,42 @@ fn static_256() {
     test_type::<Test256, UniBox256>();
 }
 
+#[test]
+fn static_tiny() {
+    let ubox = UniBox8::new(10u64).expect("Couldn't create UniBox8");
+    assert_eq!(unsafe { *ubox.as_ref::<u64>() }, 10, "Content is incorrect");
+    assert!(UniBox8::new([0u8; 9]).is_err(), "Hosted a value too big");
+    let ubox = UniBox16::new(10u128).expect("Couldn't create UniBox16");
+    assert_eq!(unsafe { *ubox.as_ref::<u128>() }, 10, "Content is incorrect");
+}
+
+#[test]
+fn static_big() {
+    test_type::<Test256, UniBox512>();
+    test_type::<Test256, UniBox1024>();
+    test_type::<Test256, UniBox4096>();
+    assert_eq!(UniBox4096::capacity(), 4096, "Wrong capacity");
+}
+
+#[cfg(not(any(feature = "diagnostics", feature = "stats")))]
+#[test]
+fn static_header() {
+    // Payload plus type metadata, id, packed lengths, metadata word and drop hook
+    assert!(core::mem::size_of::<UniBox32>() <= 32 + 6 * core::mem::size_of::<usize>(), "Header is too big");
+    let ubox = UniBox32::new(7u8).map(|ubox| ubox.with_id("seven")).expect("Couldn't create UniBox32");
+    assert_eq!((ubox.id(), ubox.len()), ("seven", 1), "Wrong id or length");
+    assert_eq!(ubox.as_ptr(), &ubox as *const UniBox32 as *const u8, "Buffer is not the first field");
+}
+
+#[test]
+fn option_niche() {
+    use core::mem::size_of;
+
+    assert_eq!(size_of::<Option<UniBox64>>(), size_of::<UniBox64>(), "Option adds a tag to static uniboxes");
+    assert_eq!(size_of::<Option<UniBoxN<[MaybeUninit<u8>; 24]>>>(), size_of::<UniBoxN<[MaybeUninit<u8>; 24]>>(), "Option adds a tag to static uniboxes");
+    assert_eq!(size_of::<Option<UniBox>>(), size_of::<UniBox>(), "Option adds a tag to heap uniboxes");
+}
+
 #[test]
 fn dynamic() {
     test_type::<Test32, UniBox>();
@@ -113,3 +154,1332 @@ fn dynamic() {
     test_type::<Test128, UniBox>();
     test_type::<Test256, UniBox>();
 }
+
+#[test]
+fn upsize() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = DROPS.load(Ordering::SeqCst);
+    let ubox = UniBox32::new_cloneable(Droppable(Test32::new())).expect("Couldn't create UniBox32").with_meta(7);
+    let ubox = UniBox128::from(UniBox64::from(ubox));
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops, "Hosted value dropped by upsize");
+    assert_eq!(ubox.meta(), 7, "Metadata lost");
+    assert!(unsafe { ubox.clone().as_ref::<Droppable>() }.0.check(), "Content is incorrect");
+    assert!(unsafe { ubox.as_ref::<Droppable>() }.0.check(), "Content is incorrect");
+    core::mem::drop(ubox);
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops + 2, "Hosted values not dropped once");
+    let ubox = UniBox64::new(Test64::new()).expect("Couldn't create UniBox64");
+    assert!(ubox.upsize::<32, 16>().is_err(), "Upsized to a unibox too small");
+    let ubox = UniBoxN::<[MaybeUninit<u8>; 64]>::new(Test64::new()).expect("Couldn't create UniBoxN").upsize::<[MaybeUninit<u8>; 100]>().expect("Couldn't upsize");
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+    let ubox = UniBox64::new_or_boxed(Test256::new()).expect("Couldn't create UniBox64");
+    let ubox: UniBox8 = ubox.upsize().expect("Couldn't move a boxed value");
+    assert!(unsafe { ubox.as_ref::<Test256>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn static_heap_conversions() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = DROPS.load(Ordering::SeqCst);
+    let ubox = UniBox64::new_cloneable(Droppable(Test32::new())).expect("Couldn't create UniBox64").with_meta(7);
+    let ubox = ubox.to_heap().expect("Couldn't move to heap");
+    assert_eq!(ubox.meta(), 7, "Metadata lost");
+    assert!(unsafe { ubox.clone().as_ref::<Droppable>() }.0.check(), "Content is incorrect");
+    let ubox = ubox.try_to_static::<UniBox32>().expect("Couldn't move to a static unibox");
+    assert!(unsafe { ubox.as_ref::<Droppable>() }.0.check(), "Content is incorrect");
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops + 1, "Hosted value dropped by a conversion");
+    core::mem::drop(ubox);
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops + 2, "Hosted value not dropped once");
+    let ubox = UniBox::new(Test64::new()).expect("Couldn't create UniBox");
+    let ubox = ubox.try_to_static::<UniBox32>().expect_err("Moved a value too big");
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+    let ubox = UniBox32::new_or_boxed(Test64::new()).expect("Couldn't create UniBox32");
+    let ubox = ubox.to_heap().expect("Couldn't move to heap");
+    assert!(unsafe { ubox.into_box::<Test64>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn type_meta() {
+    struct Droppable;
+    impl Drop for Droppable {
+        fn drop(&mut self) {}
+    }
+
+    let ubox = UniBox32::new_ord(5u32).expect("Couldn't create UniBox32").with_id("counter");
+    let type_meta = ubox.type_meta().expect("No type metadata");
+    assert_eq!(type_meta.name(), "u32", "Wrong type name");
+    assert_eq!((type_meta.size(), type_meta.align()), (4, 4), "Wrong layout");
+    assert!(type_meta.is_ord() && type_meta.is_eq() && !type_meta.is_cloneable(), "Wrong hooks");
+    assert!(!type_meta.needs_drop(), "Pod type needs drop");
+    let ubox = UniBox32::new(Droppable).expect("Couldn't create UniBox32");
+    assert!(ubox.type_meta().expect("No type metadata").needs_drop(), "Droppable type doesn't need drop");
+    let other = UniBox::new_ord(6u32).expect("Couldn't create UniBox");
+    assert_eq!(other.type_meta().map(|type_meta| type_meta.name()), Some("u32"), "Wrong type name");
+    assert!(UniBox32::empty().type_meta().is_none(), "Empty unibox has type metadata");
+    let ubox = unsafe { UniBox::from_bytes_with_id(&[1, 2, 3], "raw") }.expect("Couldn't create UniBox");
+    assert_eq!(ubox.layout(), core::alloc::Layout::from_size_align(3, 1).unwrap(), "Wrong raw layout");
+}
+
+#[test]
+fn or_boxed() {
+    let ubox = UniBox64::new_or_boxed(Test32::new()).expect("Couldn't create UniBox64");
+    assert!(!ubox.is_boxed(), "Small value was boxed");
+    let mut ubox = UniBox64::new_or_boxed(Test256::new()).expect("Couldn't create UniBox64");
+    assert!(ubox.is_boxed(), "Big value was not boxed");
+    assert_eq!(ubox.len(), 256, "Wrong length");
+    assert!(unsafe { ubox.as_ref::<Test256>() }.check(), "Content is incorrect");
+    unsafe { ubox.as_mut_ref::<Test256>() }.0[0] = 100;
+    assert_eq!(unsafe { ubox.as_bytes() }[0], 100, "Content not mutated");
+    let inner = unsafe { ubox.into_inner::<Test256>() };
+    assert_eq!(inner.0[1], 1, "Content is incorrect");
+    let ubox = UniBoxN::<[MaybeUninit<u8>; 12]>::new_or_boxed(Test64::new()).expect("Couldn't create UniBoxN");
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+    assert!(UniBoxN::<[MaybeUninit<u8>; 4]>::new_or_boxed(Test64::new()).is_err(), "Hosted a pointer too big");
+
+    #[repr(align(64))]
+    struct Marker;
+    let mut ubox = UniBox32::new_or_boxed(Marker).expect("Couldn't create UniBox32");
+    assert!(ubox.is_boxed(), "Over-aligned value was not boxed");
+    assert_eq!(ubox.as_ptr() as usize % 64, 0, "Boxed value is not aligned");
+    unsafe { ubox.take::<Marker>() };
+}
+
+#[test]
+fn auto() {
+    test_type::<Test32, AutoUniBox<32>>();
+    test_type::<Test256, AutoUniBox<32>>();
+    test_downcast::<AutoUniBox<16>>();
+    test_on_drop::<AutoUniBox<16>>();
+    test_eq_dyn::<AutoUniBox<32>>();
+    test_hash::<AutoUniBox<4>>();
+    test_cmp_dyn::<AutoUniBox<2>>();
+    let mut ubox = AutoUniBox::<32>::new(Test32::new()).expect("Couldn't create AutoUniBox");
+    assert!(ubox.is_inline(), "Small value stored on heap");
+    unsafe { ubox.replace(Test64::new()) }.expect("Couldn't replace");
+    assert!(!ubox.is_inline(), "Big value stored inline");
+    assert!(unsafe { ubox.take::<Test64>() }.check(), "Content is incorrect");
+    assert!(ubox.is_inline() && ubox.is_empty(), "Empty unibox not inline");
+    let ubox = AutoUniBox::<32>::new_cloneable(Test64::new()).expect("Couldn't create AutoUniBox");
+    assert!(unsafe { ubox.clone().as_ref::<Test64>() }.check(), "Content is incorrect");
+}
+
+fn test_eq_ct<U: Uniboxed>() {
+    let a = U::new([7u8; 16]).expect("Couldn't create a uniboxed type");
+    let b = U::new([7u8; 16]).expect("Couldn't create a uniboxed type");
+    let mut c = [7u8; 16];
+    c[15] = 8;
+    let c = U::new(c).expect("Couldn't create a uniboxed type");
+    let d = U::new([7u16; 8]).expect("Couldn't create a uniboxed type");
+    assert!(unsafe { a.eq_ct(&b) }, "Equal payloads compared as different");
+    assert!(!unsafe { a.eq_ct(&c) }, "Different payloads compared as equal");
+    assert!(!unsafe { a.eq_ct(&d) }, "Different types compared as equal");
+}
+
+#[test]
+fn constant_time_eq() {
+    test_eq_ct::<UniBox32>();
+    test_eq_ct::<UniBox256>();
+    test_eq_ct::<UniBox>();
+}
+
+fn test_downcast<U: Uniboxed>() {
+    let mut ubox = U::new_any(Test32::new()).expect("Couldn't create a uniboxed type");
+    assert!(ubox.downcast_ref::<Test32>().expect("Downcast failed").check(), "Content is incorrect");
+    assert!(ubox.downcast_ref::<[u8; 32]>().is_none(), "Downcast to a different type succeeded");
+    ubox.downcast_mut::<Test32>().expect("Mutable downcast failed").0[0] = 100;
+    assert_eq!(ubox.downcast_ref::<Test32>().expect("Downcast failed").0[0], 100, "Content not mutated");
+    let ubox = match ubox.downcast::<Test64>() {
+        Ok(_) => panic!("Consuming downcast to a different type succeeded"),
+        Err(ubox) => ubox
+    };
+    match ubox.downcast::<Test32>() {
+        Ok(inner) => assert_eq!(inner.0[0], 100, "Content is incorrect"),
+        Err(_) => panic!("Consuming downcast failed")
+    }
+    let ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    assert!(ubox.downcast_ref::<Test32>().is_none(), "Downcast without a recorded TypeId succeeded");
+}
+
+#[test]
+fn downcast() {
+    test_downcast::<UniBox32>();
+    test_downcast::<UniBox128>();
+    test_downcast::<UniBox>();
+}
+
+fn test_into_inner<U: Uniboxed>() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = DROPS.load(Ordering::SeqCst);
+    let ubox = U::new(Droppable(Test32::new())).expect("Couldn't create a uniboxed type");
+    let inner = unsafe { ubox.into_inner::<Droppable>() };
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops, "Hosted value dropped by into_inner");
+    assert!(inner.0.check(), "Content is incorrect");
+    core::mem::drop(inner);
+    assert_eq!(DROPS.load(Ordering::SeqCst), drops + 1, "Hosted value not dropped once");
+}
+
+#[test]
+fn into_inner() {
+    test_into_inner::<UniBox64>();
+    test_into_inner::<UniBox>();
+}
+
+fn test_manually_drop<U: Uniboxed>() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+    struct Handle(u32);
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            DROPPED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let ubox = U::new_manually_drop(Handle(1)).expect("Couldn't create a uniboxed type");
+    assert_eq!(unsafe { ubox.as_ref::<Handle>() }.0, 1, "Content is incorrect");
+    core::mem::drop(ubox);
+    assert!(!DROPPED.load(Ordering::SeqCst), "Hosted value was dropped");
+}
+
+#[test]
+fn manually_drop() {
+    test_manually_drop::<UniBox32>();
+    test_manually_drop::<UniBox>();
+    let ubox = UniBoxN::<[MaybeUninit<u8>; 32]>::new_manually_drop(Test32::new()).expect("Couldn't create UniBoxN");
+    assert!(unsafe { ubox.as_ref::<Test32>() }.check(), "Content is incorrect");
+}
+
+fn test_forget_inner<U: Uniboxed>() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+    struct Handle(#[allow(dead_code)] u32);
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            DROPPED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    U::new(Handle(1)).expect("Couldn't create a uniboxed type").forget_inner();
+    assert!(!DROPPED.load(Ordering::SeqCst), "Hosted value was dropped");
+}
+
+#[test]
+fn forget_inner() {
+    test_forget_inner::<UniBox32>();
+    test_forget_inner::<UniBox>();
+}
+
+fn test_on_drop<U: Uniboxed>() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use crate::DynUnibox;
+    static META: AtomicUsize = AtomicUsize::new(0);
+
+    let hook = |ubox: &mut dyn DynUnibox| {
+        META.fetch_add(ubox.meta(), Ordering::SeqCst);
+    };
+    let meta = META.load(Ordering::SeqCst);
+    let mut ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type").with_meta(1).with_on_drop(hook);
+    unsafe { ubox.take::<Test32>() };
+    unsafe { ubox.replace(Test32::new()) }.expect("Couldn't replace");
+    ubox.set_meta(10);
+    core::mem::drop(ubox);
+    assert_eq!(META.load(Ordering::SeqCst), meta + 10, "Hook not called only on drop");
+}
+
+#[test]
+fn on_drop() {
+    test_on_drop::<UniBox32>();
+    test_on_drop::<UniBox>();
+}
+
+fn test_eq_dyn<U: Uniboxed>() {
+    extern crate alloc;
+    use alloc::boxed::Box;
+
+    let a = U::new_eq(10u32).expect("Couldn't create a uniboxed type");
+    assert!(a.eq_dyn(&U::new_ord(10u32).expect("Couldn't create a uniboxed type")), "Equal values are different");
+    assert!(!a.eq_dyn(&U::new_any(10u32).expect("Couldn't create a uniboxed type")), "Comparison is not symmetric");
+    assert!(!a.eq_dyn(&U::new(11u32).expect("Couldn't create a uniboxed type")), "Different values are equal");
+    assert!(!a.eq_dyn(&U::new(10i32).expect("Couldn't create a uniboxed type")), "Different types are equal");
+    assert!(!U::new(10u32).expect("Couldn't create a uniboxed type").eq_dyn(&a), "Values without PartialEq are equal");
+    assert!(U::empty().eq_dyn(&U::empty()), "Empty uniboxes are different");
+    // Reflexive even without the PartialEq hook
+    let plain = U::new(10u32).expect("Couldn't create a uniboxed type");
+    assert!(plain.eq_dyn(&plain) && a.eq_dyn(&a), "Unibox is different from itself");
+    assert!(!a.eq_dyn(&U::new_hashable(10u32).expect("Couldn't create a uniboxed type")), "Values hashed differently are equal");
+    // Retagged uniboxes of different types must not reach the PartialEq hook
+    let boxed = U::new_eq(Box::new(1u8)).expect("Couldn't create a uniboxed type").with_id("x");
+    assert!(!boxed.eq_dyn(&U::new_eq(5usize).expect("Couldn't create a uniboxed type").with_id("x")), "Retagged types are equal");
+}
+
+#[test]
+fn eq_dyn() {
+    test_eq_dyn::<UniBox32>();
+    test_eq_dyn::<UniBox>();
+}
+
+fn test_hash<U: Uniboxed + Eq + core::hash::Hash>() {
+    extern crate std;
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    set.insert(U::new_hashable(1u32).expect("Couldn't create a uniboxed type"));
+    set.insert(U::new_hashable(1u64).expect("Couldn't create a uniboxed type"));
+    set.insert(U::new_hashable(1u32).expect("Couldn't create a uniboxed type"));
+    assert_eq!(set.len(), 2, "Duplicated key");
+    assert!(set.contains(&U::new_hashable(1u64).expect("Couldn't create a uniboxed type")), "Key not found");
+    assert!(!set.contains(&U::new_hashable(2u64).expect("Couldn't create a uniboxed type")), "Missing key found");
+}
+
+#[test]
+fn hash() {
+    test_hash::<UniBox32>();
+    test_hash::<UniBox>();
+}
+
+fn test_cmp_dyn<U: Uniboxed>() {
+    extern crate alloc;
+    use alloc::boxed::Box;
+
+    let mut v = [
+        U::new_ord(3u32).expect("Couldn't create a uniboxed type"),
+        U::new_ord(2u8).expect("Couldn't create a uniboxed type"),
+        U::new_ord(1u32).expect("Couldn't create a uniboxed type"),
+        U::empty()
+    ];
+    v.sort_by(|a, b| a.cmp_dyn(b));
+    assert!(v[0].is_empty(), "Empty unibox not first");
+    assert_eq!(unsafe { *v[1].as_ref::<u32>() }, 1, "Wrong order");
+    assert_eq!(unsafe { *v[2].as_ref::<u32>() }, 3, "Wrong order");
+    assert_eq!(v[3].id(), "u8", "Wrong order");
+    let target = U::new_ord(3u32).expect("Couldn't create a uniboxed type");
+    assert_eq!(v.binary_search_by(|probe| probe.cmp_dyn(&target)), Ok(2), "Value not found");
+    // Retagged uniboxes of different types must not reach the Ord hook
+    let boxed = U::new_ord(Box::new(1u8)).expect("Couldn't create a uniboxed type").with_id("x");
+    let other = U::new_ord(5usize).expect("Couldn't create a uniboxed type").with_id("x");
+    assert_ne!(boxed.cmp_dyn(&other), core::cmp::Ordering::Equal, "Retagged types are equivalent");
+    assert_eq!(boxed.cmp_dyn(&other), other.cmp_dyn(&boxed).reverse(), "Order is not antisymmetric");
+}
+
+#[test]
+fn cmp_dyn() {
+    test_cmp_dyn::<UniBox64>();
+    test_cmp_dyn::<UniBox>();
+}
+
+fn test_debug<U: Uniboxed + core::fmt::Debug>() {
+    extern crate alloc;
+    use alloc::format;
+
+    let ubox = U::new_debuggable(Some(10u8)).expect("Couldn't create a uniboxed type");
+    assert_eq!(format!("{:?}", ubox), "Some(10)", "Value not printed");
+    assert_eq!(format!("{:?}", ubox.as_debug().expect("Unibox is not debuggable")), "Some(10)", "Value not printed");
+    let ubox = U::new(10u8).expect("Couldn't create a uniboxed type");
+    assert!(ubox.as_debug().is_none(), "Unibox is debuggable");
+    assert!(format!("{:?}", ubox).contains("\"u8\""), "Id not printed");
+}
+
+#[test]
+fn debug() {
+    test_debug::<UniBox32>();
+    test_debug::<UniBox>();
+}
+
+fn test_clone<U: CloneUniboxed>() {
+    extern crate alloc;
+    use alloc::{string::String, vec};
+
+    let v = vec![
+        U::new_cloneable(String::from("unibox")).expect("Couldn't create a uniboxed type").with_meta(1),
+        U::new_cloneable(Test32::new()).expect("Couldn't create a uniboxed type"),
+        U::empty()
+    ];
+    let copy = v.clone();
+    assert_eq!(unsafe { copy[0].as_ref::<String>() }, "unibox", "Content is incorrect");
+    assert_eq!(copy[0].meta(), 1, "Metadata not cloned");
+    assert_ne!(copy[0].as_ptr(), v[0].as_ptr(), "Clone shares the storage");
+    assert!(unsafe { copy[1].as_ref::<Test32>() }.check(), "Content is incorrect");
+    assert!(copy[2].is_empty(), "Clone of empty unibox is not empty");
+    assert!(U::new(Test32::new()).expect("Couldn't create a uniboxed type").try_clone().is_none(), "Cloned a non cloneable unibox");
+}
+
+#[test]
+fn clone() {
+    test_clone::<UniBox32>();
+    test_clone::<UniBox>();
+}
+
+fn test_default<U: Uniboxed + Default>() {
+    let mut ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    let taken = core::mem::take(&mut ubox);
+    assert!(ubox.is_empty(), "Default unibox is not empty");
+    assert!(unsafe { taken.as_ref::<Test32>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn default() {
+    test_default::<UniBox32>();
+    test_default::<UniBox>();
+}
+
+fn test_swap<U: Uniboxed>() {
+    let mut a = U::new(Test32::new()).expect("Couldn't create a uniboxed type").with_meta(1);
+    let mut b = U::new(10u8).expect("Couldn't create a uniboxed type");
+    a.swap(&mut b);
+    assert_eq!(unsafe { *a.as_ref::<u8>() }, 10, "Content is incorrect");
+    assert_eq!((a.meta(), b.meta()), (0, 1), "Metadata not swapped");
+    assert!(unsafe { b.as_ref::<Test32>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn swap() {
+    test_swap::<UniBox32>();
+    test_swap::<UniBox>();
+}
+
+fn test_take_replace<U: Uniboxed>() {
+    let mut ubox = U::empty();
+    assert!(ubox.is_empty(), "New unibox is not empty");
+    assert!(unsafe { ubox.replace(Test32::new()) }.expect("Couldn't replace").is_none(), "Empty unibox returned a value");
+    assert!(!ubox.is_empty(), "Unibox is empty after replace");
+    let old = unsafe { ubox.replace(Test32([0; 32])) }.expect("Couldn't replace").expect("Old value not returned");
+    assert!(old.check(), "Old content is incorrect");
+    assert!(unsafe { ubox.replace(10u64) }.expect("Couldn't replace").is_none(), "Value of a different type returned");
+    assert_eq!(unsafe { ubox.take::<u64>() }, 10, "Taken content is incorrect");
+    assert!(ubox.is_empty(), "Unibox not empty after take");
+    assert_eq!(ubox.len(), 0, "Empty unibox has a length");
+}
+
+#[test]
+fn take_replace() {
+    test_take_replace::<UniBox32>();
+    test_take_replace::<UniBox>();
+    let mut ubox = UniBox32::new(1u8).expect("Couldn't create UniBox32");
+    assert!(unsafe { ubox.replace(Test64::new()) }.is_err(), "Replaced with a value too big");
+    assert_eq!(unsafe { *ubox.as_ref::<u8>() }, 1, "Hosted value lost after failed replace");
+}
+
+fn test_try_as_ref<U: Uniboxed>() {
+    let mut ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    assert!(unsafe { ubox.try_as_ref::<Test32>() }.expect("Couldn't access content").check(), "Content is incorrect");
+    assert!(matches!(unsafe { ubox.try_as_ref::<Test64>() }, Err(AccessError::TypeMismatch { .. })), "Accessed a different type");
+    unsafe { ubox.try_as_mut_ref::<Test32>() }.expect("Couldn't access content").0[0] = 100;
+    assert!(unsafe { ubox.try_take::<Test64>() }.is_err(), "Took a different type");
+    assert_eq!(unsafe { ubox.try_take::<Test32>() }.expect("Couldn't take content").0[0], 100, "Content is incorrect");
+    assert_eq!(unsafe { ubox.try_as_mut_ref::<Test32>() }.unwrap_err(), AccessError::Empty, "Accessed an empty unibox");
+    assert_eq!(unsafe { ubox.try_take::<Test32>() }.unwrap_err(), AccessError::Empty, "Took from an empty unibox");
+    let ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    let ubox = unsafe { ubox.try_into_inner::<Test64>() }.expect_err("Moved out a different type");
+    assert!(unsafe { ubox.try_into_inner::<Test32>() }.ok().expect("Couldn't move content out").check(), "Content is incorrect");
+}
+
+#[test]
+fn try_as_ref() {
+    test_try_as_ref::<UniBox32>();
+    test_try_as_ref::<UniBox>();
+    test_try_as_ref::<AutoUniBox<16>>();
+}
+
+#[test]
+fn new_with() {
+    let init = |slot: &mut core::mem::MaybeUninit<Test128>| {
+        slot.write(Test128::new());
+    };
+    let ubox = unsafe { UniBoxN::<[MaybeUninit<u8>; 128]>::new_with(init) }.expect("Couldn't create UniBoxN");
+    assert!(unsafe { ubox.as_ref::<Test128>() }.check(), "Content is incorrect");
+    let ubox = unsafe { UniBox::new_with(init) }.expect("Couldn't create UniBox");
+    assert!(unsafe { ubox.as_ref::<Test128>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn new_uninit() {
+    let mut uninit = UniBox::new_uninit::<[u64; 1024]>().expect("Couldn't allocate UniBox");
+    let values = uninit.as_mut_ptr() as *mut u64;
+    for i in 0..1024 {
+        unsafe { values.add(i).write(i as u64) };
+    }
+    let ubox = unsafe { uninit.assume_init() };
+    assert_eq!(ubox.len(), 8 * 1024, "Wrong length");
+    assert!(unsafe { ubox.as_ref::<[u64; 1024]>() }.iter().enumerate().all(|(i, v)| *v == i as u64), "Content is incorrect");
+    let mut uninit = UniBox::new_uninit::<Test128>().expect("Couldn't allocate UniBox");
+    uninit.as_uninit_mut().write(Test128::new());
+    assert!(unsafe { uninit.assume_init().as_ref::<Test128>() }.check(), "Content is incorrect");
+    UniBox::new_uninit::<Test64>().expect("Couldn't allocate UniBox");
+}
+
+fn test_ptr<U: Uniboxed>() {
+    let mut ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    let addr = unsafe { ubox.as_ref::<Test32>() } as *const Test32;
+    assert_eq!(ubox.as_ptr_of::<Test32>(), addr, "Pointer is not the stored data");
+    unsafe { *ubox.as_mut_ptr().add(1) = 100 };
+    assert_eq!(unsafe { ubox.as_ref::<Test32>() }.0[1], 100, "Content not written through pointer");
+}
+
+fn test_as_bytes<U: Uniboxed>() {
+    let ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type");
+    assert_eq!(unsafe { ubox.as_bytes() }.len(), 32, "Wrong length");
+    assert!(check_sucession(unsafe { ubox.as_bytes() }), "Bytes are incorrect");
+    assert!(unsafe { U::empty().as_bytes() }.is_empty(), "Empty unibox has bytes");
+}
+
+#[test]
+fn as_bytes() {
+    test_as_bytes::<UniBox64>();
+    test_as_bytes::<UniBox>();
+}
+
+#[test]
+fn from_bytes() {
+    let ubox = UniBox32::new(Test32::new()).expect("Couldn't create UniBox32");
+    let copy = unsafe { UniBoxN::<[MaybeUninit<u8>; 32]>::from_bytes_with_id(ubox.as_bytes(), ubox.id()) }.expect("Couldn't rebuild UniBoxN");
+    assert!(unsafe { copy.as_ref::<Test32>() }.check(), "Content is incorrect");
+    let copy = unsafe { UniBox::from_bytes_with_id(ubox.as_bytes(), ubox.id()) }.expect("Couldn't rebuild UniBox");
+    assert!(unsafe { copy.as_ref::<Test32>() }.check(), "Content is incorrect");
+    assert!(unsafe { UniBoxN::<[MaybeUninit<u8>; 32]>::from_bytes_with_id(&[0; 64], "bytes") }.is_err(), "Accepted too many bytes");
+}
+
+#[test]
+fn dyn_unibox() {
+    extern crate alloc;
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use crate::DynUnibox;
+
+    let mut v: Vec<Box<dyn DynUnibox>> = vec![
+        Box::new(UniBox32::new_any(Test32::new()).expect("Couldn't create UniBox32")),
+        Box::new(UniBox128::new(Test128::new()).expect("Couldn't create UniBox128")),
+        Box::new(UniBox::new_any(Test64::new()).expect("Couldn't create UniBox"))
+    ];
+    assert_eq!(v.iter().map(|ubox| ubox.len()).sum::<usize>(), 32 + 128 + 64, "Wrong lengths");
+    assert!(v[0].downcast_ref::<Test32>().expect("Downcast failed").check(), "Content is incorrect");
+    assert!(unsafe { (*v[1]).as_ref::<Test128>() }.check(), "Content is incorrect");
+    assert!(v[2].downcast_mut::<Test32>().is_none(), "Downcast to a different type succeeded");
+    assert!(check_sucession(unsafe { v[2].as_bytes() }), "Bytes are incorrect");
+}
+
+#[test]
+fn typed() {
+    use crate::Typed;
+
+    let mut typed = Typed::<UniBox64, Test64>::new(Test64::new()).expect("Couldn't create Typed");
+    assert!(typed.check(), "Content is incorrect");
+    typed.0[0] = 100;
+    let ubox = typed.erase();
+    assert_eq!(unsafe { ubox.as_ref::<Test64>() }.0[0], 100, "Content not written through DerefMut");
+    let typed = Typed::<UniBox, Test32>::new_any(Test32::new()).expect("Couldn't create Typed");
+    let typed = Typed::<UniBox, Test32>::from_unibox(typed.erase()).expect("Couldn't recover Typed");
+    assert!(typed.into_inner().check(), "Content is incorrect");
+    assert!(Typed::<UniBox64, Test32>::from_unibox(ubox).is_err(), "Recovered a different type");
+}
+
+fn test_unbounded<U: Uniboxed>() {
+    let arr = [1, 2, 3, 4];
+    let ubox = unsafe { U::new_unbounded(&arr[..]) }.expect("Couldn't create a uniboxed type");
+    assert_eq!(unsafe { ubox.as_ref::<&[i32]>() }, &[1, 2, 3, 4], "Content is incorrect");
+    let mut other = [5, 6];
+    let mut typed = crate::Typed::<U, &mut [i32]>::new(&mut other[..]).expect("Couldn't create Typed");
+    typed[0] = 7;
+    assert_eq!(typed.into_inner(), &[7, 6], "Content not written through DerefMut");
+}
+
+#[test]
+fn unbounded() {
+    test_unbounded::<UniBox32>();
+    test_unbounded::<UniBox>();
+}
+
+fn test_scoped<U: Uniboxed>() {
+    use crate::UniBoxScoped;
+
+    let arr = [1, 2, 3, 4];
+    let mut ubox = UniBoxScoped::<U>::new(&arr[..]).expect("Couldn't create UniBoxScoped");
+    assert_eq!(ubox.as_dyn().id(), "&[i32]", "Wrong id");
+    assert_eq!(unsafe { ubox.as_ref::<&[i32]>() }, &[1, 2, 3, 4], "Content is incorrect");
+    *unsafe { ubox.as_mut_ref::<&[i32]>() } = &arr[2..];
+    assert_eq!(unsafe { ubox.into_inner::<&[i32]>() }, &[3, 4], "Content is incorrect");
+    assert!(UniBoxScoped::<U>::empty().as_dyn().is_empty(), "Not empty");
+}
+
+#[test]
+fn scoped() {
+    test_scoped::<UniBox32>();
+    test_scoped::<UniBox>();
+}
+
+struct SelfRef {
+    data: u32,
+    ptr: *const u32,
+    _pinned: core::marker::PhantomPinned
+}
+
+impl SelfRef {
+    fn new() -> Self {
+        Self {
+            data: 10,
+            ptr: core::ptr::null(),
+            _pinned: core::marker::PhantomPinned
+        }
+    }
+
+    fn init(self: core::pin::Pin<&mut Self>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.ptr = &this.data;
+    }
+
+    fn check(self: core::pin::Pin<&Self>) -> bool {
+        core::ptr::eq(self.ptr, &self.data)
+    }
+}
+
+#[test]
+fn pinned() {
+    fn assert_unpin<T: Unpin>() {}
+    assert_unpin::<UniBox32>();
+    let ubox = UniBox32::new(SelfRef::new()).expect("Couldn't create UniBox32");
+    let mut ubox = core::pin::pin!(ubox);
+    unsafe { ubox.as_mut().as_pin_mut::<SelfRef>() }.init();
+    assert!(unsafe { ubox.as_ref().as_pin::<SelfRef>() }.check(), "Value moved");
+    let mut ubox = UniBox::pin_new(SelfRef::new()).expect("Couldn't create UniBoxPin");
+    unsafe { ubox.as_pin_mut::<SelfRef>() }.init();
+    let moved = [ubox];
+    assert!(unsafe { moved[0].as_pin::<SelfRef>() }.check(), "Value moved");
+    assert_eq!(moved[0].id(), core::any::type_name::<SelfRef>(), "Wrong id");
+}
+
+fn test_meta<U: Uniboxed>() {
+    let mut ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type").with_meta(7);
+    assert_eq!(ubox.meta(), 7, "Wrong metadata");
+    unsafe { ubox.replace(Test32::new()) }.expect("Couldn't replace");
+    assert_eq!(ubox.meta(), 7, "Metadata lost after replacing the same type");
+    unsafe { ubox.take::<Test32>() };
+    assert_eq!(ubox.meta(), 0, "Metadata not reset after take");
+}
+
+fn test_set_id<U: Uniboxed>() {
+    let ubox = U::new(Test32::new()).expect("Couldn't create a uniboxed type").with_id("test");
+    assert_eq!(ubox.id(), "test", "Id not changed");
+    assert!(unsafe { ubox.try_as_ref::<Test32>() }.is_err(), "Accessed with the old id");
+    // The TypeId still identifies retagged uniboxes
+    let mut ubox = U::new_any(Test32::new()).expect("Couldn't create a uniboxed type").with_id("test");
+    assert!(ubox.downcast_ref::<Test32>().is_some(), "Retagged unibox not downcast");
+    assert!(ubox.downcast_mut::<Test32>().is_some(), "Retagged unibox not downcast");
+    assert!(ubox.downcast::<Test32>().is_ok(), "Retagged unibox not downcast");
+    let mut ubox = U::empty();
+    ubox.set_id("test");
+    assert_eq!(ubox.id(), "", "Empty unibox has an id");
+}
+
+fn test_layout<U: Uniboxed>() {
+    let ubox = U::new(0u64).expect("Couldn't create a uniboxed type");
+    assert_eq!(ubox.layout(), core::alloc::Layout::new::<u64>(), "Wrong layout");
+    assert_eq!(U::empty().layout(), core::alloc::Layout::new::<()>(), "Wrong layout of empty unibox");
+}
+
+#[test]
+fn layout() {
+    test_layout::<UniBox32>();
+    test_layout::<UniBox>();
+}
+
+#[test]
+fn set_id() {
+    test_set_id::<UniBox32>();
+    test_set_id::<UniBox>();
+    let mut ubox = UniBoxN::<[MaybeUninit<u8>; 8]>::new_any([1u8, 2, 3]).expect("Couldn't create UniBoxN").with_id("test");
+    assert_eq!(ubox.downcast_ref::<[u8; 3]>(), Some(&[1, 2, 3]), "Retagged unibox not downcast");
+    assert!(ubox.downcast_mut::<[u8; 3]>().is_some(), "Retagged unibox not downcast");
+    assert_eq!(ubox.downcast::<[u8; 3]>().ok(), Some([1, 2, 3]), "Retagged unibox not downcast");
+}
+
+#[test]
+fn meta() {
+    test_meta::<UniBox32>();
+    test_meta::<UniBox>();
+}
+
+#[test]
+fn raw_pointers() {
+    test_ptr::<UniBox32>();
+    test_ptr::<UniBox>();
+}
+
+#[test]
+fn capacity() {
+    const CAPACITY: usize = UniBox128::capacity();
+    assert_eq!(CAPACITY, 128, "Wrong capacity");
+    let ubox = UniBox128::new(Test32::new()).expect("Couldn't create UniBox128");
+    assert_eq!(ubox.free_space(), 96, "Wrong free space");
+    assert_eq!(UniBoxN::<[MaybeUninit<u8>; 64]>::empty().free_space(), 64, "Wrong free space");
+    assert_eq!(crate::UniBoxSized::<256>::capacity(), UniBox256::capacity(), "Wrong capacity");
+    let ubox = crate::UniBoxSized::<48>::new(Test32::new()).expect("Couldn't create UniBoxSized");
+    assert_eq!(ubox.free_space(), 16, "Wrong free space");
+    assert!(crate::UniBoxSized::<24>::new(Test32::new()).is_err(), "Hosted a value too big");
+}
+
+#[test]
+fn uninit_buffer() {
+    use core::mem::MaybeUninit;
+
+    let ubox = UniBoxN::<[MaybeUninit<u8>; 64]>::new_cloneable(Test32::new()).expect("Couldn't create UniBoxN");
+    assert_eq!(ubox.free_space(), 32, "Wrong free space");
+    let copy = ubox.clone();
+    assert!(unsafe { copy.as_ref::<Test32>() }.check(), "Content is incorrect");
+    assert!(unsafe { ubox.into_inner::<Test32>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn alignment() {
+    let ubox = UniBox32::new(7u128).expect("Couldn't create UniBox32");
+    assert_eq!(ubox.as_ptr() as usize % core::mem::align_of::<u128>(), 0, "Value is misaligned");
+    assert_eq!(unsafe { *ubox.as_ref::<u128>() }, 7, "Content is incorrect");
+    assert_eq!(UniBox32::max_align(), 16, "Wrong alignment");
+    assert_eq!(
+        UniBoxN::<[MaybeUninit<u8>; 32]>::new(7u64).err(),
+        Some(UniBoxError::Misaligned { align: core::mem::align_of::<u64>(), buffer_align: 1 }),
+        "Hosted a misaligned value"
+    );
+
+    #[repr(align(64))]
+    struct CacheLine([u8; 64]);
+
+    let ubox = crate::UniBoxAligned::<64, 64>::new(CacheLine([1; 64])).expect("Couldn't create UniBoxAligned");
+    assert_eq!(ubox.as_ptr() as usize % 64, 0, "Value is misaligned");
+    assert_eq!(unsafe { ubox.as_ref::<CacheLine>() }.0, [1; 64], "Content is incorrect");
+    assert!(UniBox64::new(CacheLine([1; 64])).is_err(), "Hosted a misaligned value");
+}
+
+#[test]
+fn padding() {
+    #[derive(Clone, PartialEq, Debug)]
+    struct Padded {
+        a: u8,
+        b: u32
+    }
+
+    let ubox = UniBox32::new_cloneable(Padded { a: 1, b: 2 }).expect("Couldn't create UniBox32");
+    let copy = ubox.clone();
+    assert_eq!(unsafe { copy.as_ref::<Padded>() }, &Padded { a: 1, b: 2 }, "Content is incorrect");
+    assert_eq!(unsafe { ubox.into_inner::<Padded>() }, Padded { a: 1, b: 2 }, "Content is incorrect");
+}
+
+#[test]
+fn send_sync() {
+    extern crate std;
+    use crate::SendUniBox;
+
+    let ubox = SendUniBox::<UniBox32>::new_send(Test32::new()).expect("Couldn't create SendUniBox");
+    let ubox = std::thread::spawn(move || ubox).join().expect("Thread panicked");
+    assert!(unsafe { ubox.as_ref::<Test32>() }.check(), "Content is incorrect");
+    let ubox = SyncUniBox::<UniBox>::new_sync(Test32::new()).expect("Couldn't create SyncUniBox");
+    std::thread::scope(|s| {
+        s.spawn(|| assert!(unsafe { ubox.as_ref::<Test32>() }.check(), "Content is incorrect"));
+    });
+}
+
+#[test]
+fn new_unchecked() {
+    let ubox = unsafe { UniBoxN::<[MaybeUninit<u8>; 64]>::new_unchecked(Test64::new()) };
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn new_const() {
+    static CONFIG: SyncUniBox<UniBox32> = unsafe { SyncUniBox::from_unibox(UniBox32::new_const([1u8, 2, 3, 4], "[u8; 4]")) };
+    assert_eq!(CONFIG.id(), "[u8; 4]", "Wrong id");
+    assert_eq!(unsafe { CONFIG.as_ref::<[u8; 4]>() }, &[1, 2, 3, 4], "Content is incorrect");
+}
+
+#[test]
+fn too_big() {
+    let result = UniBox32::new(Test64::new());
+    assert_eq!(result.err(), Some(UniBoxError::TooBig { size: 64, capacity: 32 }), "Accepted a type too big");
+    let result = UniBox128::new([0u8; 200]);
+    assert_eq!(result.err().and_then(|error| error.suggested_capacity()), Some(256), "Wrong suggestion");
+    assert_eq!(UniBoxError::TooBig { size: 5000, capacity: 4096 }.suggested_capacity(), None, "Suggested a capacity too small");
+    assert_eq!(UniBoxError::ZeroSized.suggested_capacity(), None, "Suggested a capacity for another error");
+    let result = UniBox8::new([0u8; 12]);
+    assert_eq!(result.err().and_then(|error| error.suggested_capacity()), Some(<crate::smallest_unibox!([u8; 12])>::capacity()), "Suggestion differs from smallest_unibox!");
+    let _: UniBox8 = <crate::smallest_unibox!(u64)>::new(1u64).expect("Couldn't create UniBox8");
+}
+
+#[test]
+fn region() {
+    use crate::UniBoxRegion;
+
+    #[repr(align(16))]
+    struct Region([u8; 128]);
+    static mut REGION: Region = Region([0; 128]);
+
+    let region = unsafe { &mut (*core::ptr::addr_of_mut!(REGION)).0 };
+    let mut ubox = UniBoxRegion::new(region);
+    assert!(ubox.is_empty(), "New unibox is not empty");
+    assert_eq!(ubox.capacity(), 128, "Wrong capacity");
+    assert!(ubox.replace(Test64::new()).expect("Couldn't replace").is_none(), "Empty unibox returned a value");
+    assert!(ubox.downcast_ref::<Test64>().expect("Wrong type").check(), "Content is incorrect");
+    assert!(ubox.downcast_ref::<Test32>().is_none(), "Downcasted to a different type");
+    assert_eq!(ubox.fits::<Test256>(), Err(UniBoxError::TooBig { size: 256, capacity: 128 }), "Accepted a type too big");
+    assert!(ubox.replace(Test256::new()).is_err(), "Hosted a type too big");
+    assert!(ubox.replace(Test64::new()).expect("Couldn't replace").expect("No value returned").check(), "Content is incorrect");
+    assert!(ubox.take::<Test64>().expect("Couldn't take").check(), "Content is incorrect");
+    assert!(ubox.is_empty(), "Unibox not empty after take");
+    ubox.replace(7u128).expect("Couldn't replace");
+    assert_eq!(crate::DynUnibox::id(&ubox), "u128", "Wrong id");
+    ubox.replace((1u8, 2u32)).expect("Couldn't replace");
+    ubox.clear();
+    ubox.set_meta(5);
+    assert_eq!(ubox.meta(), 5, "Metadata of empty unibox ignored");
+    let region = ubox.into_region();
+    assert_eq!(region.len(), 128, "Wrong region length");
+    #[cfg(not(all(feature = "debug-poison", debug_assertions)))]
+    assert!(region[..64].iter().all(|b| *b == 0), "Used bytes not zeroed");
+    let start = region.as_mut_ptr();
+    let mut ubox = unsafe { UniBoxRegion::from_raw_parts(start, 64) };
+    assert_eq!(ubox.capacity(), 64, "Wrong capacity");
+    assert!(ubox.replace(Test128::new()).is_err(), "Hosted a type too big");
+    ubox.replace(Test64::new()).expect("Couldn't replace");
+    let (ptr, len) = ubox.into_raw_parts();
+    assert_eq!(len, 64, "Wrong region length");
+    assert_eq!(ptr, start, "Wrong region pointer");
+}
+
+#[test]
+fn bump() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use crate::UniBump;
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    static mut REGION: [u8; 256] = [0; 256];
+
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut bump = UniBump::new(unsafe { &mut *core::ptr::addr_of_mut!(REGION) });
+    assert!(bump.is_empty(), "New arena is not empty");
+    let first = bump.alloc_value(Droppable(Test32::new())).ok().expect("Couldn't allocate");
+    let byte = bump.alloc_value(7u8).expect("Couldn't allocate");
+    let second = bump.alloc_value(Test64::new()).expect("Couldn't allocate");
+    assert_eq!(bump.len(), 3, "Wrong number of values");
+    assert!(bump.used() > 32 + 1 + 64, "Wrong used bytes");
+    assert!(bump.alloc_value(Test256::new()).is_err(), "Hosted a value bigger than the region");
+    assert!(bump.get(first).expect("Wrong handle").0.check(), "Content is incorrect");
+    assert!(bump.get(second).expect("Wrong handle").check(), "Content is incorrect");
+    *bump.get_mut(byte).expect("Wrong handle") += 1;
+    assert_eq!(bump.get(byte), Some(&8), "Content not mutated");
+    while bump.alloc_value(0u64).is_ok() {}
+    assert!(bump.used() <= bump.capacity(), "Region overflowed");
+    bump.reset();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1, "Hosted values not dropped once");
+    assert!(bump.is_empty() && bump.used() == 0, "Arena not empty after reset");
+    assert!(bump.get(byte).is_none(), "Stale handle accepted");
+    let other = bump.alloc_value(9u8).expect("Couldn't allocate");
+    assert_eq!(other.index(), 0, "Wrong index");
+    bump.alloc_value((1u8, 2u32)).expect("Couldn't allocate");
+    let region = bump.into_region();
+    assert_eq!(region.len(), 256, "Wrong region length");
+    assert!(region.iter().all(|b| *b == 0), "Used bytes not zeroed");
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1, "Hosted values dropped again");
+}
+
+#[test]
+fn dynamic_replace_with() {
+    let mut ubox = UniBox::new(Test64::new()).expect("Couldn't create UniBox");
+    let buffer = ubox.as_ptr();
+    assert!(ubox.replace_with(Test32::new()).is_ok(), "Couldn't replace");
+    assert_eq!(ubox.as_ptr(), buffer, "Allocation not reused");
+    assert_eq!(ubox.id(), core::any::type_name::<Test32>(), "Wrong id");
+    assert!(unsafe { ubox.as_ref::<Test32>() }.check(), "Content is incorrect");
+    assert!(ubox.replace_with(Test256::new()).is_ok(), "Couldn't replace");
+    assert_eq!(ubox.len(), 256, "Wrong length");
+    assert!(unsafe { ubox.as_ref::<Test256>() }.check(), "Content is incorrect");
+}
+
+#[test]
+fn dynamic_aligned() {
+    let ubox = UniBox::new_aligned(Test32::new(), 4096).expect("Couldn't create an aligned UniBox");
+    let inner = unsafe { ubox.as_ref::<Test32>() };
+    assert!(inner.check(), "Content is incorrect");
+    assert_eq!(inner as *const Test32 as usize % 4096, 0, "Allocation is not aligned");
+    assert_eq!(UniBox::new_aligned(Test32::new(), 3).err(), Some(UniBoxError::InvalidAlign(3)), "Accepted a non power of two alignment");
+}
+
+#[test]
+fn dynamic_zero_sized() {
+    struct Marker;
+
+    let mut ubox = UniBox::new(Marker).expect("Couldn't create UniBox");
+    assert!(!ubox.is_empty(), "Unibox with a zero-sized type is empty");
+    assert_eq!(ubox.len(), 0, "Zero-sized type has a length");
+    unsafe { ubox.as_ref::<Marker>() };
+    assert!(unsafe { ubox.replace(()) }.expect("Couldn't replace").is_none(), "Different type returned a value");
+    let ubox = UniBox::new_aligned((), 64).expect("Couldn't create an aligned UniBox");
+    assert_eq!(ubox.as_ptr() as usize % 64, 0, "Pointer is not aligned");
+}
+
+#[test]
+fn dynamic_any() {
+    extern crate alloc;
+    use alloc::boxed::Box;
+
+    let ubox = UniBox::new_any(Test32::new()).expect("Couldn't create UniBox");
+    let buffer = ubox.as_ptr();
+    let any = ubox.into_any().expect("Couldn't convert into Box<dyn Any>");
+    let test = any.downcast_ref::<Test32>().expect("Wrong type");
+    assert!(test.check(), "Content is incorrect");
+    assert_eq!(test as *const Test32 as *const u8, buffer, "Allocation not reused");
+    let ubox = UniBox::from_any::<Test32>(any).expect("Couldn't convert from Box<dyn Any>");
+    assert_eq!(ubox.as_ptr(), buffer, "Allocation not reused");
+    assert!(ubox.downcast_ref::<Test32>().expect("Wrong type").check(), "Content is incorrect");
+    assert!(UniBox::from_any::<Test64>(Box::new(Test32::new())).is_err(), "Converted a different type");
+    assert!(UniBox::new(Test32::new()).expect("Couldn't create UniBox").into_any().is_err(), "Converted without a TypeId");
+}
+
+#[test]
+fn dynamic_raw() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let ubox = UniBox::new_any(Droppable(Test32::new())).expect("Couldn't create UniBox").with_meta(7);
+    let buffer = ubox.as_ptr();
+    let (ptr, meta) = ubox.into_raw();
+    assert_eq!(ptr as *const u8, buffer, "Wrong pointer");
+    assert_eq!(meta.id(), core::any::type_name::<Droppable>(), "Wrong id");
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0, "Hosted value dropped by into_raw");
+    let ubox = unsafe { UniBox::from_raw(ptr, meta) };
+    assert_eq!(ubox.meta(), 7, "Metadata lost");
+    assert!(ubox.downcast_ref::<Droppable>().expect("Wrong type").0.check(), "Content is incorrect");
+    core::mem::drop(ubox);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1, "Hosted value not dropped once");
+}
+
+#[test]
+fn dynamic_pool() {
+    use crate::UniPool;
+
+    let pool = UniPool::new();
+    let ubox = pool.new_box(Test64::new()).expect("Couldn't create a pooled UniBox");
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+    let buffer = ubox.as_ptr();
+    core::mem::drop(ubox);
+    assert_eq!(pool.len(), 1, "Allocation not given back");
+    let ubox = pool.new_box([7u8; 64]).expect("Couldn't create a pooled UniBox");
+    assert_eq!(ubox.as_ptr(), buffer, "Allocation not reused");
+    assert!(pool.is_empty(), "Allocation still free");
+    let other = pool.new_box(Test32::new()).expect("Couldn't create a pooled UniBox");
+    assert_ne!(other.as_ptr(), buffer, "Allocation shared");
+    core::mem::drop(other);
+    let ubox = ubox.into_unibox();
+    assert_eq!(unsafe { ubox.as_ref::<[u8; 64]>() }, &[7; 64], "Content is incorrect");
+    assert_eq!(pool.len(), 1, "Detached allocation given back");
+    pool.shrink();
+    assert!(pool.is_empty(), "Allocations not freed");
+}
+
+#[test]
+fn dynamic_arena() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use crate::UniArena;
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Droppable(Test32);
+    impl Drop for Droppable {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut arena = UniArena::new();
+    let first = arena.alloc(Droppable(Test32::new())).ok().expect("Couldn't allocate");
+    let handles: [_; 16] = core::array::from_fn(|_| arena.alloc(Test256::new()).expect("Couldn't allocate"));
+    let byte = arena.alloc(7u8).expect("Couldn't allocate");
+    assert_eq!(arena.len(), 18, "Wrong number of values");
+    assert!(arena.capacity() > 32 + 16 * 256, "Buffer didn't grow");
+    assert!(arena.get(first).expect("Wrong handle").0.check(), "Content moved wrong");
+    assert!(handles.iter().all(|handle| arena.get(*handle).expect("Wrong handle").check()), "Content moved wrong");
+    *arena.get_mut(byte).expect("Wrong handle") += 1;
+    assert_eq!(arena.get(byte), Some(&8), "Content not mutated");
+    arena.clear();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1, "Hosted values not dropped once");
+    assert!(arena.get(byte).is_none(), "Stale handle accepted");
+    let other = arena.alloc(9u8).expect("Couldn't allocate");
+    assert_eq!(other.index(), byte.index() - 17, "Wrong index");
+    assert!(arena.get(first).is_none(), "Stale handle accepted");
+    core::mem::drop(arena);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1, "Hosted values dropped again");
+}
+
+#[test]
+fn dynamic_slotmap() {
+    extern crate alloc;
+    use crate::UniSlotMap;
+
+    let mut map = UniSlotMap::new();
+    let first = map.insert(UniBox::new_any(Test32::new()).expect("Couldn't create UniBox"));
+    let second = map.insert(UniBox::new_any(Test64::new()).expect("Couldn't create UniBox"));
+    assert_eq!(map.len(), 2, "Wrong number of uniboxes");
+    assert!(map.get(first).and_then(|ubox| ubox.downcast_ref::<Test32>()).expect("Wrong key").check(), "Content is incorrect");
+    assert!(map.remove(first).expect("Couldn't remove").downcast_ref::<Test32>().expect("Wrong type").check(), "Content is incorrect");
+    assert!(map.remove(first).is_none(), "Removed twice");
+    let third = map.insert(UniBox::new_any(7u8).expect("Couldn't create UniBox"));
+    assert_eq!(third.index(), first.index(), "Slot not reused");
+    assert!(map.get(first).is_none() && !map.contains_key(first), "Stale key accepted");
+    *map.get_mut(third).and_then(|ubox| ubox.downcast_mut::<u8>()).expect("Wrong key") += 1;
+    assert_eq!(map.iter().map(|(key, _)| key).collect::<alloc::vec::Vec<_>>(), [third, second], "Wrong iteration");
+    assert_eq!(map.iter_mut().filter_map(|(_, ubox)| ubox.downcast_mut::<u8>().copied()).sum::<u8>(), 8, "Content not mutated");
+    map.clear();
+    assert!(map.is_empty() && map.get(second).is_none(), "Map not cleared");
+    let mut inline = UniSlotMap::<UniBox16>::with_capacity(1);
+    let key = inline.insert(UniBox16::new_any(5u32).expect("Couldn't create UniBox16"));
+    assert_eq!(inline.get(key).and_then(|ubox| ubox.downcast_ref::<u32>()), Some(&5), "Content is incorrect");
+}
+
+#[test]
+fn dynamic_rc() {
+    use crate::UniRc;
+
+    let rc = UniRc::new_any(Test64::new()).expect("Couldn't create UniRc");
+    let buffer = rc.as_ptr();
+    let mut shared = rc.clone();
+    assert_eq!(UniRc::strong_count(&rc), 2, "Wrong number of owners");
+    assert!(UniRc::ptr_eq(&rc, &shared) && shared.as_ptr() == buffer, "Hosted value not shared");
+    assert!(shared.downcast_ref::<Test64>().expect("Wrong type").check(), "Content is incorrect");
+    assert!(UniRc::get_mut(&mut shared).is_none(), "Mutable access with other owners");
+    let rc = UniRc::try_unwrap(rc).expect_err("Unwrapped with other owners");
+    core::mem::drop(shared);
+    let mut rc = rc;
+    assert!(UniRc::get_mut(&mut rc).and_then(|ubox| ubox.downcast_mut::<Test64>()).is_some(), "No mutable access to the only owner");
+    let ubox = UniRc::try_unwrap(rc).expect("Couldn't unwrap the only owner");
+    assert_eq!(ubox.as_ptr(), buffer, "Hosted value moved");
+    let rc = UniRc::from(ubox);
+    assert_eq!(rc.id(), core::any::type_name::<Test64>(), "Wrong id");
+}
+
+#[test]
+fn dynamic_arc() {
+    extern crate std;
+    use crate::UniArc;
+
+    let arc = UniArc::new_any(Test64::new()).expect("Couldn't create UniArc");
+    let sent = arc.clone();
+    std::thread::spawn(move || assert!(sent.downcast_ref::<Test64>().expect("Wrong type").check(), "Content is incorrect")).join().expect("Thread panicked");
+    let buffer = arc.as_ptr();
+    let mut other = arc.clone();
+    assert_eq!(UniArc::strong_count(&arc), 2, "Wrong number of owners");
+    assert!(UniArc::ptr_eq(&arc, &other) && other.as_ptr() == buffer, "Hosted value not shared");
+    assert!(other.downcast_ref::<Test64>().expect("Wrong type").check(), "Content is incorrect");
+    assert!(unsafe { UniArc::get_mut(&mut other) }.is_none(), "Mutable access with other owners");
+    let mut arc = UniArc::try_unwrap(arc).expect_err("Unwrapped with other owners");
+    core::mem::drop(other);
+    assert!(unsafe { UniArc::get_mut(&mut arc) }.is_some(), "No mutable access to the only owner");
+    let ubox = UniArc::try_unwrap(arc).expect("Couldn't unwrap the only owner");
+    assert_eq!(ubox.as_ptr(), buffer, "Hosted value moved");
+    let arc = UniArc::from(SyncUniBox::<UniBox>::new_sync(7u32).expect("Couldn't create SyncUniBox"));
+    assert_eq!(unsafe { arc.as_ref::<u32>() }, &7, "Content is incorrect");
+}
+
+#[test]
+fn dynamic_cow() {
+    use crate::UniCow;
+
+    let cow = UniCow::new(Test64::new()).expect("Couldn't create UniCow");
+    let buffer = cow.as_ptr();
+    let mut edited = cow.clone();
+    assert!(cow.is_shared() && edited.as_ptr() == buffer, "Hosted value not shared");
+    assert!(edited.downcast_mut::<Test32>().is_none(), "Downcasted to a different type");
+    assert!(edited.is_shared(), "Cloned for a different type");
+    edited.downcast_mut::<Test64>().expect("Couldn't clone").0[0] = 100;
+    assert!(!cow.is_shared() && edited.as_ptr() != buffer, "Hosted value not cloned");
+    assert!(cow.downcast_ref::<Test64>().expect("Wrong type").check(), "Shared value mutated");
+    assert_eq!(edited.downcast_ref::<Test64>().expect("Wrong type").0[0], 100, "Clone not mutated");
+    let copy = cow.clone();
+    assert_ne!(copy.into_unibox().expect("Couldn't clone").as_ptr(), buffer, "Shared value not cloned");
+    assert_eq!(cow.into_unibox().expect("Couldn't unwrap").as_ptr(), buffer, "Only owner cloned");
+}
+
+#[test]
+fn dynamic_box_conversions() {
+    extern crate alloc;
+    use alloc::boxed::Box;
+
+    let instance = Box::new(Test64::new());
+    let buffer = &*instance as *const Test64 as *const u8;
+    let ubox = UniBox::from_box(instance);
+    assert_eq!(ubox.as_ptr(), buffer, "Allocation not reused");
+    assert_eq!(ubox.id(), core::any::type_name::<Test64>(), "Wrong id");
+    assert!(unsafe { ubox.as_ref::<Test64>() }.check(), "Content is incorrect");
+    let instance = unsafe { ubox.into_box::<Test64>() };
+    assert_eq!(&*instance as *const Test64 as *const u8, buffer, "Allocation not reused");
+    assert!(instance.check(), "Content is incorrect");
+    let ubox = UniBox::new_aligned(Test32::new(), 64).expect("Couldn't create UniBox");
+    assert!(unsafe { ubox.into_box::<Test32>() }.check(), "Content is incorrect");
+    let ubox = UniBox::from_box(Box::new(()));
+    assert_eq!(ubox.len(), 0, "Wrong length");
+}
+
+#[test]
+fn dynamic_leak() {
+    let ubox = UniBox::new(Test32::new()).expect("Couldn't create UniBox");
+    let leaked: &'static mut Test32 = unsafe { ubox.leak() };
+    assert!(leaked.check(), "Content is incorrect");
+}
+
+#[test]
+fn dynamic_alloc_error_hook() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+    set_alloc_error_hook(Some(|_| {
+        FAILURES.fetch_add(1, Ordering::SeqCst);
+    }));
+    set_alloc_retries(2);
+    // No allocator can satisfy such an alignment
+    let result = UniBox::new_aligned(Test32::new(), 1 << 60);
+    set_alloc_retries(0);
+    set_alloc_error_hook(None);
+    assert!(matches!(result, Err(UniBoxError::AllocFailed(_))), "Allocation should fail");
+    assert_eq!(FAILURES.load(Ordering::SeqCst), 3, "Hook not called on every failure");
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroizing() {
+    use zeroize::Zeroize;
+
+    struct Secret([u8; 16]);
+    impl Zeroize for Secret {
+        fn zeroize(&mut self) {
+            self.0.zeroize();
+        }
+    }
+
+    let mut ubox = UniBox32::new_zeroizing(Secret([0xAA; 16])).expect("Couldn't create a zeroizing UniBox32");
+    ubox.zeroize();
+    assert_eq!(unsafe { ubox.as_ref::<Secret>() }.0, [0; 16], "Secret was not wiped");
+
+    let mut ubox = UniBox::new_zeroizing(Secret([0xAA; 16])).expect("Couldn't create a zeroizing UniBox");
+    ubox.zeroize();
+    assert_eq!(unsafe { ubox.as_ref::<Secret>() }.0, [0; 16], "Secret was not wiped");
+
+    let mut ubox = UniBox32::new_secret([0xAAu8; 16]).expect("Couldn't create a secret UniBox32");
+    assert_eq!(unsafe { ubox.take::<[u8; 16]>() }, [0xAA; 16], "Content is incorrect");
+    let buffer = unsafe { core::slice::from_raw_parts(ubox.as_ptr(), UniBox32::capacity()) };
+    assert!(!buffer.contains(&0xAA), "Secret lingers in the buffer");
+}
+
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+#[test]
+fn debug_poison() {
+    use crate::UniBoxRegion;
+
+    static mut REGION: [u8; 64] = [0; 64];
+
+    let region = unsafe { &mut *core::ptr::addr_of_mut!(REGION) };
+    let mut ubox = UniBoxRegion::new(region);
+    ubox.replace(Test32::new()).expect("Couldn't replace");
+    let region = ubox.into_region();
+    assert!(region[..32].iter().all(|b| *b == 0xDD), "Dropped value was not poisoned");
+    assert!(region[32..].iter().all(|b| *b == 0), "Poisoned beyond the dropped value");
+}
+
+/// Counts the deallocations of boxed `[u8; 173]` values that were poisoned before being freed.
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+mod poison_check {
+    extern crate std;
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::alloc::System;
+
+    pub const SIZE: usize = 173;
+    pub static POISONED: AtomicUsize = AtomicUsize::new(0);
+
+    struct PoisonCheck;
+
+    unsafe impl GlobalAlloc for PoisonCheck {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            if layout == Layout::new::<[u8; SIZE]>() && core::slice::from_raw_parts(ptr, SIZE).iter().all(|b| *b == 0xDD) {
+                POISONED.fetch_add(1, Ordering::Relaxed);
+            }
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: PoisonCheck = PoisonCheck;
+}
+
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+#[test]
+fn debug_poison_boxed() {
+    use core::sync::atomic::Ordering;
+    use poison_check::{POISONED, SIZE};
+
+    let ubox = UniBox32::new_or_boxed([1u8; SIZE]).expect("Couldn't create UniBox32");
+    core::mem::drop(ubox);
+    assert_eq!(POISONED.load(Ordering::Relaxed), 1, "Dropped boxed value was not poisoned");
+    UniBox32::new_or_boxed([2u8; SIZE]).expect("Couldn't create UniBox32").forget_inner();
+    assert_eq!(POISONED.load(Ordering::Relaxed), 2, "Forgotten boxed value was not poisoned");
+    let mut ubox = UniBox32::new_or_boxed([3u8; SIZE]).expect("Couldn't create UniBox32");
+    assert_eq!(unsafe { ubox.take::<[u8; SIZE]>() }, [3; SIZE], "Content is incorrect");
+    assert_eq!(POISONED.load(Ordering::Relaxed), 3, "Moved out boxed value was not poisoned");
+}
+
+#[cfg(feature = "diagnostics")]
+#[test]
+fn diagnostics_dump_live() {
+    extern crate alloc;
+    use alloc::string::String;
+
+    struct Diagnosed;
+    let ubox = UniBox64::new(Diagnosed).expect("Couldn't create UniBox64");
+    let mut dump = String::new();
+    crate::dump_live(&mut dump).expect("Couldn't dump live uniboxes");
+    assert!(dump.contains("Diagnosed (0 bytes) created at src/tests.rs"), "Live unibox not found in dump");
+    core::mem::drop(ubox);
+    let mut dump = String::new();
+    crate::dump_live(&mut dump).expect("Couldn't dump live uniboxes");
+    assert!(!dump.contains("Diagnosed"), "Dropped unibox found in dump");
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn stats_counters() {
+    struct Counted(#[allow(dead_code)] [u8; 24]);
+    let count = || crate::stats_by_id().iter().flatten().find(|(id, _)| *id == core::any::type_name::<Counted>()).map_or(0, |(_, count)| *count);
+
+    let before = crate::stats();
+    let first = UniBox64::new(Counted([0; 24])).expect("Couldn't create UniBox64");
+    let mut second = UniBox::new(Counted([0; 24])).expect("Couldn't create UniBox");
+    assert_eq!(count(), 2, "Wrong count of live values");
+    let after = crate::stats();
+    assert!(after.created >= before.created + 2, "Created uniboxes not counted");
+    assert!(after.live >= 2 && after.bytes >= 48 && after.peak_bytes >= 48, "Live uniboxes not counted");
+    core::mem::drop(first);
+    assert_eq!(count(), 1, "Dropped value still counted");
+    second.set_id("renamed");
+    assert_eq!(count(), 0, "Renamed value still counted");
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn new_zeroed() {
+    let ubox = UniBox64::new_zeroed::<[u64; 8]>().expect("Couldn't create UniBox64");
+    assert_eq!(unsafe { ubox.as_ref::<[u64; 8]>() }, &[0; 8], "Content is not zeroed");
+    let ubox = UniBox::new_zeroed::<[u64; 1024]>().expect("Couldn't create UniBox");
+    assert!(unsafe { ubox.as_ref::<[u64; 1024]>() }.iter().all(|v| *v == 0), "Content is not zeroed");
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn pod_clone() {
+    let ubox = UniBox32::new_pod([1u16, 2, 3]).expect("Couldn't create UniBox32");
+    assert!(ubox.is_cloneable(), "Pod value is not cloneable");
+    assert_eq!(unsafe { ubox.clone().as_ref::<[u16; 3]>() }, &[1, 2, 3], "Content is incorrect");
+    let ubox = UniBox::new_pod([1u16, 2, 3]).expect("Couldn't create UniBox");
+    assert!(ubox.type_meta().expect("No type metadata").is_pod(), "Pod value not recorded");
+    assert_eq!(unsafe { ubox.clone().as_ref::<[u16; 3]>() }, &[1, 2, 3], "Content is incorrect");
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn shared_segment() {
+    use crate::{SharedError, SharedHeader};
+
+    let mut segment = [0u8; 64];
+    let ubox = UniBox32::new_pod([1u32, 2, 3]).expect("Couldn't create UniBox32").with_meta(7);
+    assert_eq!(ubox.write_shared(&mut segment), Ok(SharedHeader::LEN + 12), "Wrong record size");
+    let header = SharedHeader::read(&segment).expect("Invalid header");
+    assert_eq!((header.layout(), header.meta()), (Some(core::alloc::Layout::new::<[u32; 3]>()), 7), "Wrong header");
+    let shared = UniBox::from_shared::<[u32; 3]>(&segment).expect("Couldn't rebuild UniBox");
+    assert_eq!(unsafe { shared.as_ref::<[u32; 3]>() }, &[1, 2, 3], "Content is incorrect");
+    assert_eq!(shared.meta(), 7, "Metadata not restored");
+    let shared = UniBox16::from_shared::<[u32; 3]>(&segment).expect("Couldn't rebuild UniBox16");
+    assert!(shared.type_meta().expect("No type metadata").is_pod(), "Pod value not recorded");
+    assert!(matches!(UniBox::from_shared::<[u16; 6]>(&segment), Err(SharedError::LayoutMismatch { .. })), "Accepted a different layout");
+    assert_eq!(UniBox::from_shared::<[i32; 3]>(&segment).err(), Some(SharedError::TypeMismatch("[i32; 3]")), "Accepted a different type");
+    assert!(matches!(UniBox::from_shared::<[u32; 3]>(&segment[..40]), Err(SharedError::TooSmall { size: 44, available: 40 })), "Accepted a truncated segment");
+    assert_eq!(UniBox::new(5u8).expect("Couldn't create UniBox").write_shared(&mut segment), Err(SharedError::NotPod), "Wrote a value that is not Pod");
+    assert_eq!(ubox.write_shared(&mut segment[..40]).err(), Some(SharedError::TooSmall { size: 44, available: 40 }), "Wrote past the segment");
+    segment[4] ^= 0xFF;
+    assert!(matches!(SharedHeader::read(&segment), Err(SharedError::Version(_))), "Accepted another version");
+    segment[0] = 0;
+    assert_eq!(SharedHeader::read(&segment), Err(SharedError::BadMagic), "Accepted a segment without a record");
+}
+
+#[test]
+fn dyn_trait() {
+    trait Shape {
+        fn area(&self) -> u32;
+        fn scale(&mut self, factor: u32);
+    }
+
+    struct Square(u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 { self.0 * self.0 }
+        fn scale(&mut self, factor: u32) { self.0 *= factor }
+    }
+
+    struct Rect(u32, u32);
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 { self.0 * self.1 }
+        fn scale(&mut self, factor: u32) { self.0 *= factor; self.1 *= factor }
+    }
+
+    crate::unibox_dyn!(ShapeDyn = dyn Shape);
+
+    let mut shapes = [
+        UniBox32::new_dyn::<ShapeDyn, _>(Square(2)).expect("Couldn't create UniBox32"),
+        UniBox32::new_dyn::<ShapeDyn, _>(Rect(2, 3)).expect("Couldn't create UniBox32")
+    ];
+    for shape in shapes.iter_mut() {
+        shape.as_dyn_mut::<dyn Shape>().expect("Not a Shape").scale(2);
+    }
+    assert_eq!(shapes.iter().map(|shape| shape.as_dyn::<dyn Shape>().expect("Not a Shape").area()).sum::<u32>(), 40, "Wrong areas");
+    assert!(shapes[0].as_dyn::<dyn core::fmt::Debug>().is_none(), "Accessed as another trait");
+    assert!(shapes[1].downcast_ref::<Rect>().is_some(), "Type not recorded");
+    let ubox = UniBox::new_dyn::<ShapeDyn, _>(Square(3)).expect("Couldn't create UniBox");
+    assert_eq!(ubox.as_dyn::<dyn Shape>().map(Shape::area), Some(9), "Wrong area");
+    assert!(UniBox::new(Square(3)).expect("Couldn't create UniBox").as_dyn::<dyn Shape>().is_none(), "Accessed without a trait object");
+}
@@ -0,0 +1,103 @@
+use core::{
+    any::TypeId,
+    marker::PhantomData,
+    ops::{
+        Deref,
+        DerefMut
+    }
+};
+use crate::{Uniboxed, UniBoxError};
+
+/// Unibox that remembers the hosted type `T` at the type level, giving safe access to it.
+/// 
+/// Since `T` is part of the signature, the compiler keeps tracking its lifetimes, and the value can be accessed through [`Deref`] and [`DerefMut`] without unsafe code. When different types must be stored together, [`Typed::erase`] returns the untyped unibox.
+/// 
+/// ```
+/// use unibox::{ Typed, Uniboxed, UniBox32 };
+/// 
+/// let mut num = Typed::<UniBox32, u64>::new(10).expect("Couldn't create Typed");
+/// *num += 1;
+/// assert_eq!(*num, 11);
+/// 
+/// let ubox: UniBox32 = num.erase();
+/// assert_eq!(ubox.id(), "u64");
+/// ```
+pub struct Typed<U: Uniboxed, T> {
+    unibox: U,
+    _type: PhantomData<T>
+}
+
+// The hosted type is always `T`, so it decides
+unsafe impl<U: Uniboxed, T: Send> Send for Typed<U, T> {}
+unsafe impl<U: Uniboxed, T: Sync> Sync for Typed<U, T> {}
+
+impl<U: Uniboxed, T> Typed<U, T> {
+    /// Create a new Typed instance.
+    ///
+    /// Returns Err if the type can't be hosted by `U`, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            // Lifetimes of `T` are still tracked through `_type`
+            unibox: unsafe { U::new_unbounded(instance)? },
+            _type: PhantomData
+        })
+    }
+
+    /// Move the hosted value out, consuming the unibox.
+    pub fn into_inner(self) -> T {
+        unsafe { self.unibox.into_inner() }
+    }
+
+    /// Reference to the untyped unibox.
+    pub fn as_unibox(&self) -> &U {
+        &self.unibox
+    }
+}
+
+impl<U: Uniboxed, T: 'static> Typed<U, T> {
+    /// Convert into the untyped unibox, to store it along with other types.
+    pub fn erase(self) -> U {
+        self.unibox
+    }
+
+    /// Create a new Typed instance that also records the [`TypeId`] of `T`, so it can be recovered with [`Typed::from_unibox`] after being erased.
+    ///
+    /// Returns Err if the type can't be hosted by `U`, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    pub fn new_any(instance: T) -> Result<Self, UniBoxError> {
+        Ok(Self {
+            unibox: U::new_any(instance)?,
+            _type: PhantomData
+        })
+    }
+
+    /// Recover a Typed instance from an untyped unibox created with [`Uniboxed::new_any`].
+    ///
+    /// Returns Err with the unibox if the hosted type is unknown or different.
+    pub fn from_unibox(unibox: U) -> Result<Self, U> {
+        if unibox.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Ok(Self {
+                unibox,
+                _type: PhantomData
+            })
+        }
+        else {
+            Err(unibox)
+        }
+    }
+}
+
+impl<U: Uniboxed, T> Deref for Typed<U, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.unibox.as_ptr_of::<T>() }
+    }
+}
+
+impl<U: Uniboxed, T> DerefMut for Typed<U, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.unibox.as_mut_ptr_of::<T>() }
+    }
+}
@@ -0,0 +1,292 @@
+use core::{
+    alloc::Layout,
+    any::TypeId,
+    cmp::Ordering,
+    fmt::{
+        self,
+        Debug
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    marker::PhantomData,
+    mem,
+    ptr
+};
+use crate::DynCast;
+
+// Coercion to a trait object, the TypeId of the trait object and a `fn(*mut u8) -> *mut D` without its type
+type DynCastFn = (fn() -> TypeId, fn());
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+#[cfg(feature = "bytemuck")]
+use bytemuck::Pod;
+
+/// Description of a hosted type, shared by all the uniboxes hosting it. See [`Uniboxed::type_meta`](crate::Uniboxed::type_meta).
+///
+/// A `&'static TypeMeta` is generated at compile time for each type and constructor, so a unibox only stores a pointer to it instead of the size, alignment and hooks of the type. The compiler may duplicate it, so don't compare their addresses.
+pub struct TypeMeta {
+    pub(crate) name: fn() -> &'static str,
+    pub(crate) size: usize,
+    pub(crate) align: usize,
+    // Hooks get pointers to the hosted value, so they don't depend on the kind of unibox. Types without drop glue have no drop_fn
+    pub(crate) drop_fn: Option<fn(*mut u8)>,
+    pub(crate) type_id: Option<fn() -> TypeId>,
+    pub(crate) clone: Option<fn(*const u8, *mut u8)>,
+    pub(crate) eq: Option<fn(*const u8, *const u8) -> bool>,
+    pub(crate) hash: Option<fn(*const u8, &mut dyn Hasher)>,
+    pub(crate) cmp: Option<fn(*const u8, *const u8) -> Ordering>,
+    pub(crate) debug: Option<fn(*const u8, &mut fmt::Formatter<'_>) -> fmt::Result>,
+    #[cfg(feature = "zeroize")]
+    pub(crate) zeroize: Option<fn(*mut u8)>,
+    pub(crate) dyn_cast: Option<DynCastFn>,
+    // Pod values are cloned by copying their bytes
+    #[cfg(feature = "bytemuck")]
+    pub(crate) pod: bool
+}
+
+impl TypeMeta {
+    /// Metadata of values rebuilt from raw bytes, whose type is unknown. They are never dropped, and their size is the one of the bytes.
+    pub(crate) const RAW: Self = Self {
+        name: || "",
+        size: 0,
+        align: 1,
+        drop_fn: None,
+        type_id: None,
+        clone: None,
+        eq: None,
+        hash: None,
+        cmp: None,
+        debug: None,
+        #[cfg(feature = "zeroize")]
+        zeroize: None,
+        dyn_cast: None,
+        #[cfg(feature = "bytemuck")]
+        pod: false
+    };
+
+    /// Metadata of type `T`, without any of the optional hooks.
+    pub const fn of<T>() -> &'static Self {
+        &TypeMetaOf::<T>::BASE
+    }
+
+    /// Name of the type, from [`core::any::type_name`]. Unlike [`Uniboxed::id`](crate::Uniboxed::id), it can't be changed.
+    pub fn name(&self) -> &'static str {
+        (self.name)()
+    }
+
+    /// Size of the type.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Alignment of the type.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Size and alignment of the type.
+    pub fn layout(&self) -> Layout {
+        unsafe { Layout::from_size_align_unchecked(self.size, self.align) }
+    }
+
+    /// The type has drop glue, see [`core::mem::needs_drop`]. Otherwise dropping the unibox doesn't call into the hosted type.
+    pub fn needs_drop(&self) -> bool {
+        self.drop_fn.is_some()
+    }
+
+    /// [`TypeId`] of the type, only known if the unibox was created with [`Uniboxed::new_any`](crate::Uniboxed::new_any) or a constructor that compares values, like [`Uniboxed::new_eq`](crate::Uniboxed::new_eq).
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.type_id.map(|type_id| type_id())
+    }
+
+    /// The hosted value can be cloned, see [`CloneUniboxed::new_cloneable`](crate::CloneUniboxed::new_cloneable).
+    pub fn is_cloneable(&self) -> bool {
+        #[cfg(feature = "bytemuck")]
+        if self.pod {
+            return true;
+        }
+        self.clone.is_some()
+    }
+
+    /// The type is [`Pod`], so the hosted value is cloned by copying its bytes. See [`UniBoxN::new_pod`](crate::UniBoxN::new_pod).
+    #[cfg(feature = "bytemuck")]
+    pub fn is_pod(&self) -> bool {
+        self.pod
+    }
+
+    /// The hosted value can be compared, see [`Uniboxed::eq_dyn`](crate::Uniboxed::eq_dyn).
+    pub fn is_eq(&self) -> bool {
+        self.eq.is_some()
+    }
+
+    /// The hosted value can be hashed, see [`Uniboxed::new_hashable`](crate::Uniboxed::new_hashable).
+    pub fn is_hashable(&self) -> bool {
+        self.hash.is_some()
+    }
+
+    /// The hosted value can be ordered, see [`Uniboxed::cmp_dyn`](crate::Uniboxed::cmp_dyn).
+    pub fn is_ord(&self) -> bool {
+        self.cmp.is_some()
+    }
+
+    /// The hosted value can be printed, see [`Uniboxed::as_debug`](crate::Uniboxed::as_debug).
+    pub fn is_debuggable(&self) -> bool {
+        self.debug.is_some()
+    }
+
+    /// The hosted value can be accessed as a trait object, see [`Uniboxed::as_dyn`](crate::Uniboxed::as_dyn).
+    pub fn is_dyn(&self) -> bool {
+        self.dyn_cast.is_some()
+    }
+
+    /// Coercion of the hosted value to the trait object `D`, if it was recorded for `D`.
+    pub(crate) fn dyn_cast<D: ?Sized + 'static>(&self) -> Option<fn(*mut u8) -> *mut D> {
+        let (type_id, cast) = self.dyn_cast?;
+        if type_id() != TypeId::of::<D>() {
+            return None;
+        }
+        // Recorded by TypeMetaDyn as this same type
+        Some(unsafe { mem::transmute::<fn(), fn(*mut u8) -> *mut D>(cast) })
+    }
+}
+
+impl Debug for TypeMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeMeta").field("name", &self.name()).field("size", &self.size).field("align", &self.align).finish_non_exhaustive()
+    }
+}
+
+/// Holds the metadata of type `T` for every constructor, as associated consts promoted to statics.
+pub(crate) struct TypeMetaOf<T>(PhantomData<T>);
+
+impl<T> TypeMetaOf<T> {
+    pub(crate) const BASE: TypeMeta = TypeMeta {
+        name: core::any::type_name::<T>,
+        size: mem::size_of::<T>(),
+        align: mem::align_of::<T>(),
+        drop_fn: if mem::needs_drop::<T>() { Some(Self::drop_fn) } else { None },
+        type_id: None,
+        clone: None,
+        eq: None,
+        hash: None,
+        cmp: None,
+        debug: None,
+        #[cfg(feature = "zeroize")]
+        zeroize: None,
+        dyn_cast: None,
+        #[cfg(feature = "bytemuck")]
+        pod: false
+    };
+
+    fn drop_fn(hosted: *mut u8) {
+        unsafe { ptr::drop_in_place(hosted as *mut T) };
+    }
+}
+
+impl<T: 'static> TypeMetaOf<T> {
+    pub(crate) const ANY: TypeMeta = TypeMeta {
+        type_id: Some(TypeId::of::<T>),
+        ..Self::BASE
+    };
+}
+
+// Comparisons also record the TypeId, so they only call the hook when both values are a `T`
+impl<T: PartialEq + 'static> TypeMetaOf<T> {
+    pub(crate) const EQ: TypeMeta = TypeMeta {
+        eq: Some(Self::eq_fn),
+        ..Self::ANY
+    };
+
+    fn eq_fn(hosted: *const u8, other: *const u8) -> bool {
+        unsafe { *(hosted as *const T) == *(other as *const T) }
+    }
+}
+
+impl<T: Hash + Eq + 'static> TypeMetaOf<T> {
+    pub(crate) const HASH: TypeMeta = TypeMeta {
+        hash: Some(Self::hash_fn),
+        ..Self::EQ
+    };
+
+    fn hash_fn(hosted: *const u8, mut state: &mut dyn Hasher) {
+        unsafe { &*(hosted as *const T) }.hash(&mut state);
+    }
+}
+
+impl<T: Ord + 'static> TypeMetaOf<T> {
+    pub(crate) const ORD: TypeMeta = TypeMeta {
+        cmp: Some(Self::cmp_fn),
+        ..Self::EQ
+    };
+
+    fn cmp_fn(hosted: *const u8, other: *const u8) -> Ordering {
+        unsafe { (*(hosted as *const T)).cmp(&*(other as *const T)) }
+    }
+}
+
+impl<T: Debug> TypeMetaOf<T> {
+    pub(crate) const DEBUG: TypeMeta = TypeMeta {
+        debug: Some(Self::debug_fn),
+        ..Self::BASE
+    };
+
+    fn debug_fn(hosted: *const u8, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        unsafe { &*(hosted as *const T) }.fmt(f)
+    }
+}
+
+impl<T: Clone> TypeMetaOf<T> {
+    pub(crate) const CLONE: TypeMeta = TypeMeta {
+        clone: Some(Self::clone_fn),
+        ..Self::BASE
+    };
+
+    fn clone_fn(hosted: *const u8, target: *mut u8) {
+        unsafe { ptr::write(target as *mut T, (*(hosted as *const T)).clone()) };
+    }
+}
+
+// Only used by UniCow
+#[cfg(feature = "alloc")]
+impl<T: Clone + 'static> TypeMetaOf<T> {
+    pub(crate) const CLONE_ANY: TypeMeta = TypeMeta {
+        type_id: Some(TypeId::of::<T>),
+        ..Self::CLONE
+    };
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Zeroize> TypeMetaOf<T> {
+    pub(crate) const ZEROIZE: TypeMeta = TypeMeta {
+        zeroize: Some(Self::zeroize_fn),
+        ..Self::BASE
+    };
+
+    fn zeroize_fn(hosted: *mut u8) {
+        unsafe { &mut *(hosted as *mut T) }.zeroize();
+    }
+}
+
+/// Holds the metadata of type `T` hosted as the trait object of `C`.
+pub(crate) struct TypeMetaDyn<T, C>(PhantomData<(T, C)>);
+
+impl<T: 'static, C: DynCast<T>> TypeMetaDyn<T, C> {
+    pub(crate) const DYN: TypeMeta = TypeMeta {
+        dyn_cast: Some((TypeId::of::<C::Dyn>, unsafe { mem::transmute::<fn(*mut u8) -> *mut C::Dyn, fn()>(Self::cast_fn) })),
+        ..TypeMetaOf::<T>::ANY
+    };
+
+    fn cast_fn(hosted: *mut u8) -> *mut C::Dyn {
+        C::cast(hosted as *mut T)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: Pod> TypeMetaOf<T> {
+    pub(crate) const POD: TypeMeta = TypeMeta {
+        pod: true,
+        ..Self::BASE
+    };
+}
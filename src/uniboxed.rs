@@ -1,21 +1,323 @@
+use core::{
+    alloc::Layout,
+    any::TypeId
+};
+use crate::{AccessError, OnDropHook, TypeMeta, UniBoxError};
+#[cfg(feature = "bytemuck")]
+use crate::SharedError;
+
 /// Generic trait for all uniboxes.
 pub trait Uniboxed {
     /// Create a new UniBox instance.
-    fn new<T: Sized>(instance: T) -> Result<Self, ()> where Self: Sized;
+    /// 
+    /// The hosted type must be `'static`, so the unibox can't outlive any reference inside it. Types with shorter lifetimes can be hosted with [`Uniboxed::new_unbounded`].
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        unsafe { Self::new_unbounded(instance) }
+    }
+    /// Create a new UniBox instance hosting a type that may contain non-static references.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    /// 
+    /// # Safety
+    /// 
+    /// The compiler can't track the lifetimes of the hosted value anymore. The caller must make sure the unibox is dropped, or its value taken out, before any reference inside it becomes invalid.
+    unsafe fn new_unbounded<T: Sized>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create a new UniBox instance that records the [`TypeId`] of the hosted type, enabling [`Uniboxed::downcast_ref`] and [`Uniboxed::downcast_mut`].
+    fn new_any<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create a new UniBox instance whose hosted value is never dropped, like [`ManuallyDrop`](core::mem::ManuallyDrop).
+    /// 
+    /// For values whose lifetime is managed externally, e.g. arena-owned data or FFI handles freed by C. The value can still be moved out with [`Uniboxed::take`].
+    #[cfg_attr(feature = "diagnostics", track_caller)]
+    fn new_manually_drop<T: 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized {
+        Ok(Self::new(core::mem::ManuallyDrop::new(instance))?.with_id(core::any::type_name::<T>()))
+    }
+    /// Create a new UniBox instance that records the [`PartialEq`] implementation of the hosted type, enabling [`Uniboxed::eq_dyn`].
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    fn new_eq<T: PartialEq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create a new UniBox instance that records the [`Hash`](core::hash::Hash) and [`PartialEq`] implementations of the hosted type, so the unibox can be used as a key in hash maps.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    fn new_hashable<T: core::hash::Hash + Eq + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create a new UniBox instance that records the [`Ord`] implementation of the hosted type, enabling [`Uniboxed::cmp_dyn`] and [`Uniboxed::eq_dyn`].
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    fn new_ord<T: Ord + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create a new UniBox instance that records the [`Debug`](core::fmt::Debug) implementation of the hosted type, so printing the unibox prints the value.
+    /// 
+    /// Returns Err if the type can't be hosted, see [`UniBoxError`].
+    fn new_debuggable<T: core::fmt::Debug + 'static>(instance: T) -> Result<Self, UniBoxError> where Self: Sized;
+    /// Create an empty UniBox instance, that hosts no value.
+    fn empty() -> Self where Self: Sized;
     /// Get reference to stored data using a type.
     /// 
     /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
     unsafe fn as_ref<T: Sized>(&self) -> &T;
     /// Get mutable reference to stored data using a type.
     /// 
     /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
     unsafe fn as_mut_ref<T: Sized>(&mut self) -> &mut T;
+    /// Get reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn try_as_ref<T: Sized>(&self) -> Result<&T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&*self.as_ptr_of::<T>())
+    }
+    /// Get mutable reference to stored data using a type, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn try_as_mut_ref<T: Sized>(&mut self) -> Result<&mut T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(&mut *self.as_mut_ptr_of::<T>())
+    }
+    /// Move stored data out of the unibox if it is of type `T`, otherwise give the unibox back.
+    /// 
+    /// Safe alternative to [`Uniboxed::into_inner`], returns Err if the unibox wasn't created with [`Uniboxed::new_any`] or the type is different.
+    fn downcast<T: 'static>(self) -> Result<T, Self> where Self: Sized {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            // The id may have been changed, so the value is read without the checks of take
+            let instance = unsafe { core::ptr::read(self.as_ptr_of::<T>()) };
+            self.forget_inner();
+            Ok(instance)
+        }
+        else {
+            Err(self)
+        }
+    }
+    /// Move stored data out of the unibox, consuming it.
+    /// 
+    /// The hosted value is returned, not dropped.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn into_inner<T: Sized>(mut self) -> T where Self: Sized {
+        self.take()
+    }
+    /// Move stored data out of the unibox, leaving it empty.
+    /// 
+    /// **WARNING**: If you try to cast a type other than the one actually hosted, it will panic.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn take<T: Sized>(&mut self) -> T;
+    /// Move stored data out of the unibox, leaving it empty, without panicking.
+    /// 
+    /// Returns Err if the unibox is empty or hosts a different type. The hosted value is kept in that case.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn try_take<T: Sized>(&mut self) -> Result<T, AccessError> {
+        AccessError::check::<T>(self.is_empty(), self.id(), self.len())?;
+        Ok(self.take())
+    }
+    /// Move stored data out of the unibox, consuming it, without panicking.
+    /// 
+    /// Returns Err with the unibox if it is empty or hosts a different type.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn try_into_inner<T: Sized>(mut self) -> Result<T, Self> where Self: Sized {
+        match self.try_take() {
+            Ok(instance) => Ok(instance),
+            Err(_) => Err(self)
+        }
+    }
+    /// Consume the unibox without running the destructor of the hosted value, e.g. when its resources were already handed off elsewhere (DMA, shared memory).
+    /// 
+    /// Like [`core::mem::forget`], the value is leaked, but the storage of the unibox is still released.
+    fn forget_inner(self) where Self: Sized;
+    /// Put a new value in the unibox.
+    /// 
+    /// If the hosted type is the same, the value is swapped and the old one returned. Otherwise the hosted value, if any, is dropped, and the new one gets an id assigned with [`core::any::type_name`].
+    /// 
+    /// Returns Err with the new value if it can't be stored (too big or allocation failed). The hosted value is kept in that case.
+    /// 
+    /// # Safety
+    /// 
+    /// The hosted value may contain references whose lifetime the compiler can't track anymore. The caller must make sure they are still valid.
+    unsafe fn replace<T: Sized>(&mut self, instance: T) -> Result<Option<T>, T>;
+    /// Exchange the hosted values, with their ids, metadata and hooks, between two uniboxes. No value is dropped.
+    fn swap(&mut self, other: &mut Self) where Self: Sized {
+        core::mem::swap(self, other);
+    }
+    /// The unibox hosts no value.
+    fn is_empty(&self) -> bool;
+    /// Raw pointer to stored data.
+    fn as_ptr(&self) -> *const u8;
+    /// Raw mutable pointer to stored data.
+    fn as_mut_ptr(&mut self) -> *mut u8;
+    /// Raw pointer to stored data, cast to `T`. No type checks are done.
+    fn as_ptr_of<T: Sized>(&self) -> *const T {
+        self.as_ptr() as *const T
+    }
+    /// Raw mutable pointer to stored data, cast to `T`. No type checks are done.
+    fn as_mut_ptr_of<T: Sized>(&mut self) -> *mut T {
+        self.as_mut_ptr() as *mut T
+    }
+    /// Stored data as a byte slice of [`Uniboxed::len`] bytes, e.g. to hash, checksum or persist it.
+    /// 
+    /// Pointers in the hosted value are only valid in the current process.
+    /// 
+    /// # Safety
+    /// 
+    /// Every byte of the hosted value must be initialized: the type must have no padding bytes nor uninitialized fields, like a [`Pod`](https://docs.rs/bytemuck/latest/bytemuck/trait.Pod.html) type or a value rebuilt from bytes.
+    unsafe fn as_bytes(&self) -> &[u8] {
+        core::slice::from_raw_parts(self.as_ptr(), self.len())
+    }
     /// Stored data length.
     fn len(&self) -> usize;
+    /// Size and alignment of the hosted type, e.g. to validate casts. An empty unibox has the layout of `()`.
+    /// 
+    /// For uniboxes rebuilt from raw bytes the alignment is unknown, and reported as 1.
+    fn layout(&self) -> Layout;
     /// Type identifier. Automatically assigned with [`core::any::type_name`].
     fn id(&self) -> &'static str;
+    /// Change the type identifier, e.g. to re-tag a unibox received from a generic producer. Does nothing if the unibox is empty.
+    /// 
+    /// The new id is the one checked by [`Uniboxed::as_ref`] and friends. Static uniboxes truncate ids longer than 4 GiB.
+    fn set_id(&mut self, id: &'static str);
+    /// Change the type identifier, builder style. See [`Uniboxed::set_id`].
+    fn with_id(mut self, id: &'static str) -> Self where Self: Sized {
+        self.set_id(id);
+        self
+    }
+    /// [`TypeId`] of the hosted type, only known if the unibox was created with [`Uniboxed::new_any`] or a constructor that compares values, like [`Uniboxed::new_eq`].
+    fn hosted_type_id(&self) -> Option<TypeId> {
+        self.type_meta().and_then(TypeMeta::type_id)
+    }
+    /// Description of the hosted type, shared by all the uniboxes hosting it: name, size, alignment and the hooks recorded by the constructor. None if the unibox is empty.
+    fn type_meta(&self) -> Option<&'static TypeMeta>;
+    /// User metadata word attached to the hosted value, 0 by default.
+    fn meta(&self) -> usize;
+    /// Set the user metadata word. It's reset to 0 when the hosted value is dropped or moved out.
+    fn set_meta(&mut self, meta: usize);
+    /// Attach a user metadata word, e.g. flags or priority, builder style.
+    fn with_meta(mut self, meta: usize) -> Self where Self: Sized {
+        self.set_meta(meta);
+        self
+    }
+    /// Register a hook called right before every hosted value is dropped, e.g. to log, flush, or return a pooled resource.
+    /// 
+    /// The hook gets the unibox still hosting the value. It isn't called when the value is moved out or forgotten.
+    fn set_on_drop(&mut self, hook: OnDropHook);
+    /// Register a drop hook, builder style. See [`Uniboxed::set_on_drop`].
+    fn with_on_drop(mut self, hook: OnDropHook) -> Self where Self: Sized {
+        self.set_on_drop(hook);
+        self
+    }
+    /// Get reference to stored data if it is of type `T`.
+    /// 
+    /// Safe alternative to [`Uniboxed::as_ref`], returns None if the unibox wasn't created with [`Uniboxed::new_any`] or the type is different.
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &*self.as_ptr_of::<T>() })
+        }
+        else {
+            None
+        }
+    }
+    /// Get mutable reference to stored data if it is of type `T`.
+    /// 
+    /// Safe alternative to [`Uniboxed::as_mut_ref`], returns None if the unibox wasn't created with [`Uniboxed::new_any`] or the type is different.
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if self.hosted_type_id() == Some(TypeId::of::<T>()) {
+            Some(unsafe { &mut *self.as_mut_ptr_of::<T>() })
+        }
+        else {
+            None
+        }
+    }
+    /// The hosted value as a [`Debug`](core::fmt::Debug) trait object, if the unibox was created with [`Uniboxed::new_debuggable`].
+    fn as_debug(&self) -> Option<&dyn core::fmt::Debug>;
+    /// The hosted value as the trait object `D`, if the unibox was created with `new_dyn` for `D`, e.g. [`UniBoxN::new_dyn`](crate::UniBoxN::new_dyn).
+    fn as_dyn<D: ?Sized + 'static>(&self) -> Option<&D> {
+        let cast = self.type_meta()?.dyn_cast::<D>()?;
+        Some(unsafe { &*cast(self.as_ptr() as *mut u8) })
+    }
+    /// The hosted value as the mutable trait object `D`, if the unibox was created with `new_dyn` for `D`.
+    fn as_dyn_mut<D: ?Sized + 'static>(&mut self) -> Option<&mut D> {
+        let cast = self.type_meta()?.dyn_cast::<D>()?;
+        Some(unsafe { &mut *cast(self.as_mut_ptr()) })
+    }
+    /// Compare the hosted values of two uniboxes, e.g. to deduplicate a queue of mixed payloads.
+    /// 
+    /// Values are equal if they have the same id and type, and the [`PartialEq`] implementation recorded by both of them with [`Uniboxed::new_eq`], [`Uniboxed::new_hashable`] or [`Uniboxed::new_ord`] says so. The type is checked with its [`TypeId`], since ids can be changed with [`Uniboxed::set_id`]. Uniboxes created otherwise are only equal to themselves, and two empty uniboxes are always equal, so the comparison is reflexive. Uniboxes created with [`Uniboxed::new_hashable`] are never equal to uniboxes created otherwise, to keep it consistent with [`Hash`](core::hash::Hash).
+    fn eq_dyn(&self, other: &Self) -> bool where Self: Sized;
+    /// Order two uniboxes, e.g. to sort and binary search a collection of mixed payloads.
+    /// 
+    /// The order is total: empty uniboxes go first, then uniboxes are ordered by id (and length), uniboxes with the same id by type, and uniboxes of the same type by the [`Ord`] implementation recorded by [`Uniboxed::new_ord`], after the ones without it. Uniboxes without it are considered equivalent. The order of types comes from their [`TypeId`], so it's only stable for the same build.
+    /// 
+    /// Uniboxes don't implement [`Ord`], because equivalent uniboxes aren't always equal, use this with `sort_by` and `binary_search_by`.
+    fn cmp_dyn(&self, other: &Self) -> core::cmp::Ordering where Self: Sized;
+    /// Compare the hosted data of two uniboxes in constant time.
+    /// 
+    /// Ids and lengths are compared first, as they are not secret. Then the payload bytes are compared without early exit, so the time taken doesn't depend on where they differ.
+    /// 
+    /// **WARNING**: Only meaningful for Pod-like types without pointers. Otherwise equal values may compare as different.
+    /// 
+    /// # Safety
+    /// 
+    /// Every byte of both hosted values must be initialized, see [`Uniboxed::as_bytes`].
+    unsafe fn eq_ct(&self, other: &Self) -> bool where Self: Sized {
+        self.id() == other.id() && self.len() == other.len() && ct_eq_bytes(self.as_bytes(), other.as_bytes())
+    }
+    /// Copy the hosted value to a shared-memory segment, after a [`SharedHeader`](crate::SharedHeader), so another process can rebuild it with `from_shared`, e.g. [`UniBoxN::from_shared`](crate::UniBoxN::from_shared).
+    /// 
+    /// Returns the number of bytes written, or Err if the value was not created with `new_pod` or the segment is too small.
+    #[cfg(feature = "bytemuck")]
+    fn write_shared(&self, segment: &mut [u8]) -> Result<usize, SharedError> {
+        crate::shared::write_shared(self.type_meta(), self.meta(), self.as_ptr(), segment)
+    }
     /// Check if the provided and hosted types are the same.
     fn check_type<T>(&self) -> bool {
         self.len() == core::mem::size_of::<T>() && self.id() == core::any::type_name::<T>()
     }
 }
+
+/// Pattern written over the storage of a dropped value with the `debug-poison` feature.
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+pub(crate) const POISON_BYTE: u8 = 0xDD;
+
+/// Compare two byte slices in constant time.
+pub(crate) fn ct_eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+/// Hosted value of a unibox, with its id and metadata, to move it between different kinds of uniboxes without knowing its type.
+pub(crate) struct Hosted {
+    pub(crate) id: &'static str,
+    pub(crate) len: usize,
+    pub(crate) type_meta: Option<&'static TypeMeta>,
+    pub(crate) meta: usize,
+    pub(crate) on_drop: Option<OnDropHook>
+}